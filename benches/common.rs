@@ -2,7 +2,7 @@ use std::{fs, rc::Rc};
 
 use anyhow::{anyhow, Context};
 
-use puzzle::{puzzle_by_day, PuzzleInput, PuzzleSolutionFn};
+use puzzle::{day_puzzle, DayPuzzle, PuzzleInput};
 
 #[derive(Debug, Clone)]
 pub struct StringLines {
@@ -42,13 +42,13 @@ impl Iterator for StringLines {
     }
 }
 
-pub fn bench_day(day: usize) -> anyhow::Result<(PuzzleSolutionFn, StringLines)> {
+pub fn bench_day(day: usize) -> anyhow::Result<(DayPuzzle, StringLines)> {
     let file_path = format!("src/day{day}/input.txt");
 
     let input: StringLines = fs::read_to_string(&file_path)
         .with_context(|| format!("Puzzle input '{file_path}' not found!"))?
         .into();
-    let solution = puzzle_by_day(day).ok_or(anyhow!("Puzzle solution {day} not found!"))?;
+    let puzzle = day_puzzle(day).ok_or(anyhow!("Puzzle solution {day} not found!"))?;
 
-    Ok((solution, input))
+    Ok((puzzle, input))
 }
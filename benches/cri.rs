@@ -2,20 +2,21 @@ mod common;
 
 use criterion::{criterion_group, criterion_main, Criterion};
 
-fn day_benchmark(c: &mut Criterion, day: usize) -> anyhow::Result<()> {
-    let (solution, input) = common::bench_day(day)?;
+fn day_benchmark(c: &mut Criterion, day: usize, parts: u16) -> anyhow::Result<()> {
+    let (puzzle, input) = common::bench_day(day)?;
 
-    for part in 1..=2 {
+    for part in 1..=parts {
+        let params = puzzle.params(part, &[])?;
         c.bench_function(&format!("day {day} part{part}"), |b| {
-            b.iter(|| solution(input.clone().puzzle_input(), part == 2))
+            b.iter(|| (puzzle.solve)(input.clone().puzzle_input(), part == 2, &params))
         });
     }
     Ok(())
 }
 
 pub fn all_days_benchmark(c: &mut Criterion) {
-    for day in 1..=25 {
-        if let Err(err) = day_benchmark(c, day) {
+    for puzzle in puzzle::DAYS {
+        if let Err(err) = day_benchmark(c, puzzle.day, puzzle.parts) {
             println!("{}", err);
         }
     }
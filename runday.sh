@@ -2,14 +2,9 @@
 day=${1:?"Missing day argument"}
 INPUT_FILE="src/day${day}/input.txt"
 SESSION_KEY_FILE=session.txt
-if [ ! -f "$INPUT_FILE" ] && [ -f "$SESSION_KEY_FILE" ]
+if [ ! -s "$INPUT_FILE" ] && [ -f "$SESSION_KEY_FILE" ]
 then
-	curl "https://adventofcode.com/2023/day/$day/input" \
-		--compressed \
-		-A "curl/$(curl --version | head -n 1 | cut -f2-3 -d' ') ($(cargo read-manifest | jq -r '.homepage,.authors[0]' | tr '\n' ' '))" \
-		-H "Cookie: session=$(cat "$SESSION_KEY_FILE")" \
-		--create-dirs \
-		--output "$INPUT_FILE"
+	cargo run -rq -- fetch "$day" --session-file "$SESSION_KEY_FILE"
 fi
 for part in 1 2
 do
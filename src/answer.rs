@@ -0,0 +1,199 @@
+use std::{cmp::Ordering, convert::Infallible, fmt::Display, str::FromStr};
+
+use serde::{
+    de::{self, Visitor},
+    Deserialize, Deserializer, Serialize,
+};
+
+/// Answer of a puzzle part.
+/// Integers compare by value regardless of their variant and before any text.
+/// Serialized as a string, but deserialized from a string or an integer.
+#[derive(Debug, Clone, Serialize)]
+#[serde(into = "String")]
+pub enum Answer {
+    Integer(u128),
+    Signed(i128),
+    Text(String),
+    /// The input has no answer for the given reason
+    Unsolved(String),
+}
+
+/// Integer value ordered across the signed and unsigned ranges
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Number {
+    Negative(i128),
+    NonNegative(u128),
+}
+
+impl Answer {
+    fn number(&self) -> Option<Number> {
+        match *self {
+            Answer::Integer(n) => Some(Number::NonNegative(n)),
+            Answer::Signed(n) if n < 0 => Some(Number::Negative(n)),
+            Answer::Signed(n) => Some(Number::NonNegative(n as u128)),
+            Answer::Text(_) | Answer::Unsolved(_) => None,
+        }
+    }
+
+    pub fn is_number(&self) -> bool {
+        self.number().is_some()
+    }
+
+    pub fn is_solved(&self) -> bool {
+        !matches!(self, Answer::Unsolved(_))
+    }
+}
+
+impl PartialEq for Answer {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Answer {}
+
+impl PartialOrd for Answer {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Answer {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (Answer::Unsolved(a), Answer::Unsolved(b)) => a.cmp(b),
+            (Answer::Unsolved(_), _) => Ordering::Greater,
+            (_, Answer::Unsolved(_)) => Ordering::Less,
+            (Answer::Text(a), Answer::Text(b)) => a.cmp(b),
+            (Answer::Text(_), _) => Ordering::Greater,
+            (_, Answer::Text(_)) => Ordering::Less,
+            _ => self.number().cmp(&other.number()),
+        }
+    }
+}
+
+impl Display for Answer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Answer::Integer(n) => n.fmt(f),
+            Answer::Signed(n) => n.fmt(f),
+            Answer::Text(text) => text.fmt(f),
+            Answer::Unsolved(reason) => format!("unsolved: {reason}").fmt(f),
+        }
+    }
+}
+
+impl FromStr for Answer {
+    type Err = Infallible;
+
+    /// Integers are parsed as numbers, anything else is kept as text
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(if let Ok(n) = s.parse() {
+            Answer::Integer(n)
+        } else if let Ok(n) = s.parse() {
+            Answer::Signed(n)
+        } else {
+            Answer::Text(s.to_string())
+        })
+    }
+}
+
+impl From<String> for Answer {
+    fn from(value: String) -> Self {
+        match value.parse() {
+            Ok(answer) => answer,
+            Err(infallible) => match infallible {},
+        }
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(value: &str) -> Self {
+        value.to_string().into()
+    }
+}
+
+impl From<Answer> for String {
+    fn from(value: Answer) -> Self {
+        value.to_string()
+    }
+}
+
+struct AnswerVisitor;
+
+impl<'de> Visitor<'de> for AnswerVisitor {
+    type Value = Answer;
+
+    fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str("an answer string or integer")
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<Answer, E> {
+        Ok(value.into())
+    }
+
+    fn visit_i64<E: de::Error>(self, value: i64) -> Result<Answer, E> {
+        Ok(value.into())
+    }
+
+    fn visit_u64<E: de::Error>(self, value: u64) -> Result<Answer, E> {
+        Ok(value.into())
+    }
+
+    fn visit_i128<E: de::Error>(self, value: i128) -> Result<Answer, E> {
+        Ok(value.into())
+    }
+
+    fn visit_u128<E: de::Error>(self, value: u128) -> Result<Answer, E> {
+        Ok(value.into())
+    }
+}
+
+impl<'de> Deserialize<'de> for Answer {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(AnswerVisitor)
+    }
+}
+
+macro_rules! answer_from_integers {
+    ($variant:ident: $wide:ty => $($int:ty),*) => {
+        $(impl From<$int> for Answer {
+            fn from(value: $int) -> Self {
+                Answer::$variant(value as $wide)
+            }
+        })*
+    };
+}
+
+answer_from_integers!(Integer: u128 => u8, u16, u32, u64, u128, usize);
+answer_from_integers!(Signed: i128 => i8, i16, i32, i64, i128, isize);
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_compare() {
+        assert_eq!(Answer::from(5_u32), Answer::from(5_i64));
+        assert!(Answer::from(-3) < Answer::from(2_usize));
+        assert!(Answer::from(u128::MAX) > Answer::from(i128::MAX));
+        assert!(Answer::from(7_u8) < Answer::from("abc"));
+        assert_eq!(Answer::from("07"), Answer::from(7_u8));
+        let unsolved = Answer::Unsolved("no path".into());
+        assert!(!unsolved.is_solved() && Answer::from("no path").is_solved());
+        assert_ne!(unsolved, Answer::from("no path"));
+        assert_eq!(unsolved.to_string(), "unsolved: no path");
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!("42".parse::<Answer>(), Ok(Answer::Integer(42)));
+        assert_eq!("-42".parse::<Answer>(), Ok(Answer::Signed(-42)));
+        assert_eq!("EGJGF".parse::<Answer>(), Ok(Answer::Text("EGJGF".into())));
+        assert_eq!(Answer::from(-42).to_string(), "-42");
+        assert_eq!(
+            format!("{:>4}|{:<3}|", Answer::from(7_u8), Answer::from("ab")),
+            "   7|ab |"
+        );
+    }
+}
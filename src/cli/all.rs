@@ -0,0 +1,55 @@
+use std::{ops::RangeInclusive, time::Duration};
+
+use puzzle::{Answer, DAYS};
+
+use super::{input_path, solve_day};
+
+const ANSWER_WIDTH: usize = 16;
+
+/// Run every registered day in `days` and print a timing table.
+/// Returns `false` if any puzzle input failed to load or parse or any part is unsolved.
+pub fn run_all(days: RangeInclusive<usize>) -> bool {
+    let mut success = true;
+    let mut total = Duration::ZERO;
+    println!(
+        "{:>3} | {:>10} | {:<w$} | {:>10} | {:<w$} | {:>10}",
+        "Day",
+        "Parse",
+        "Part 1",
+        "Time",
+        "Part 2",
+        "Time",
+        w = ANSWER_WIDTH
+    );
+    println!("{}", "-".repeat(48 + 2 * ANSWER_WIDTH));
+    for puzzle in DAYS.iter().filter(|puzzle| days.contains(&puzzle.day)) {
+        let day = puzzle.day;
+        match solve_day(day, puzzle.solve_timed) {
+            Ok(Some(solution)) => {
+                success &= solution.parts().all(|(_, timed)| timed.answer.is_solved());
+                total += solution.total_duration();
+                let (part2_answer, part2_duration) = match &solution.part2 {
+                    Some(part2) => (part2.answer.clone(), format!("{:.3?}", part2.duration)),
+                    None => (Answer::Text("-".into()), "-".to_string()),
+                };
+                println!(
+                    "{:>3} | {:>10.3?} | {:<w$} | {:>10.3?} | {:<w$} | {:>10}",
+                    day,
+                    solution.parse_duration,
+                    solution.part1.answer,
+                    solution.part1.duration,
+                    part2_answer,
+                    part2_duration,
+                    w = ANSWER_WIDTH
+                );
+            }
+            Ok(None) => println!("{:>3} | skipped (no input in {})", day, input_path(day)),
+            Err(err) => {
+                success = false;
+                println!("{:>3} | error: {}", day, err);
+            }
+        }
+    }
+    println!("Total: {:.3?}", total);
+    success
+}
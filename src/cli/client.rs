@@ -0,0 +1,318 @@
+use std::{
+    error::Error,
+    fmt::Display,
+    fs, io,
+    path::{Path, PathBuf},
+    thread,
+    time::{Duration, SystemTime},
+};
+
+use anyhow::Context;
+use clap::Args;
+
+pub const DEFAULT_BASE_URL: &str = "https://adventofcode.com/2023";
+
+/// Minimal time between two requests to the puzzle server
+pub const REQUEST_INTERVAL: Duration = Duration::from_secs(5);
+
+/// File whose modification time records the last request to the puzzle server
+pub const REQUEST_STAMP_FILE: &str = ".last_request";
+
+#[derive(Args, Debug)]
+pub struct ServerArgs {
+    /// Base URL of the puzzle server
+    #[arg(long, value_name = "URL", env = "AOC_BASE_URL", default_value = DEFAULT_BASE_URL)]
+    pub base_url: String,
+
+    /// File containing the session key of the puzzle server
+    #[arg(long, value_name = "FILE", default_value = "session.txt")]
+    pub session_file: PathBuf,
+}
+
+impl ServerArgs {
+    pub fn client(&self) -> anyhow::Result<AocClient> {
+        let session = fs::read_to_string(&self.session_file)
+            .with_context(|| format!("Failed reading session key from {:?}", self.session_file))?;
+        Ok(AocClient::new(
+            &self.base_url,
+            session.trim(),
+            RateLimiter::new(REQUEST_STAMP_FILE, REQUEST_INTERVAL),
+        ))
+    }
+}
+
+/// Identify this tool by its package metadata like `runday.sh` did
+pub fn user_agent() -> String {
+    format!(
+        "{}/{} ({} {})",
+        env!("CARGO_PKG_NAME"),
+        env!("CARGO_PKG_VERSION"),
+        env!("CARGO_PKG_HOMEPAGE"),
+        env!("CARGO_PKG_AUTHORS")
+    )
+}
+
+/// Failed request to the puzzle server
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AocError {
+    SessionExpired,
+    Locked { day: usize },
+    Status { code: u16, body: String },
+    Transport(String),
+}
+
+impl Display for AocError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AocError::SessionExpired => {
+                write!(
+                    f,
+                    "session key is invalid or expired, update the session file"
+                )
+            }
+            AocError::Locked { day } => write!(f, "day {day} is not unlocked yet"),
+            AocError::Status { code, body } => {
+                write!(f, "unexpected response status {code}: {}", body.trim())
+            }
+            AocError::Transport(err) => write!(f, "request failed: {err}"),
+        }
+    }
+}
+
+impl Error for AocError {}
+
+/// Keeps at least `interval` between requests, also across separate runs
+pub struct RateLimiter {
+    stamp_file: PathBuf,
+    interval: Duration,
+}
+
+impl RateLimiter {
+    pub fn new(stamp_file: impl Into<PathBuf>, interval: Duration) -> Self {
+        Self {
+            stamp_file: stamp_file.into(),
+            interval,
+        }
+    }
+
+    /// Sleep until `interval` has passed since the last request and record a new request
+    pub fn wait(&self) -> io::Result<()> {
+        let elapsed = fs::metadata(&self.stamp_file)
+            .and_then(|meta| meta.modified())
+            .ok()
+            .and_then(|last| SystemTime::now().duration_since(last).ok());
+        if let Some(remaining) = elapsed.and_then(|elapsed| self.interval.checked_sub(elapsed)) {
+            thread::sleep(remaining);
+        }
+        fs::write(&self.stamp_file, "")
+    }
+}
+
+/// Client of the puzzle server authenticated by a session key
+pub struct AocClient {
+    base_url: String,
+    session: String,
+    agent: ureq::Agent,
+    rate_limit: RateLimiter,
+}
+
+impl AocClient {
+    pub fn new(base_url: &str, session: &str, rate_limit: RateLimiter) -> Self {
+        Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            session: session.to_string(),
+            agent: ureq::AgentBuilder::new()
+                .user_agent(&user_agent())
+                .redirects(0)
+                .build(),
+            rate_limit,
+        }
+    }
+
+    fn request(&self, method: &str, path: &str) -> Result<ureq::Request, AocError> {
+        self.rate_limit
+            .wait()
+            .map_err(|err| AocError::Transport(err.to_string()))?;
+        Ok(self
+            .agent
+            .request(method, &format!("{}{}", self.base_url, path))
+            .set("Cookie", &format!("session={}", self.session)))
+    }
+
+    /// Read the body of a response and classify error statuses of `day`
+    fn response_text(
+        day: usize,
+        response: Result<ureq::Response, ureq::Error>,
+    ) -> Result<String, AocError> {
+        match response {
+            Ok(response) if response.status() < 300 => response
+                .into_string()
+                .map_err(|err| AocError::Transport(err.to_string())),
+            Ok(_) => Err(AocError::SessionExpired),
+            Err(ureq::Error::Status(code, response)) => {
+                let body = response.into_string().unwrap_or_default();
+                Err(match code {
+                    400 | 401 | 403 => AocError::SessionExpired,
+                    404 => AocError::Locked { day },
+                    _ => AocError::Status { code, body },
+                })
+            }
+            Err(err) => Err(AocError::Transport(err.to_string())),
+        }
+    }
+
+    pub fn get_input(&self, day: usize) -> Result<String, AocError> {
+        let request = self.request("GET", &format!("/day/{day}/input"))?;
+        Self::response_text(day, request.call())
+    }
+
+    /// Post `answer` of `part` and return the HTML of the response page
+    pub fn post_answer(&self, day: usize, part: u16, answer: &str) -> Result<String, AocError> {
+        let request = self.request("POST", &format!("/day/{day}/answer"))?;
+        Self::response_text(
+            day,
+            request.send_form(&[("level", &part.to_string()), ("answer", answer)]),
+        )
+    }
+}
+
+/// Download the puzzle input of `day` to `path` unless it is already cached there.
+/// An empty placeholder file is replaced. Returns `true` if the input was downloaded.
+pub fn fetch_input(client: &AocClient, day: usize, path: &Path) -> anyhow::Result<bool> {
+    if super::has_input(path) {
+        return Ok(false);
+    }
+    let input = client
+        .get_input(day)
+        .with_context(|| format!("Failed fetching input of day {day}"))?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(path, input).with_context(|| format!("Failed writing {path:?}"))?;
+    Ok(true)
+}
+
+/// Local stand-in for the puzzle server replying `responses` in order.
+/// Returns its base URL and a handle yielding the raw received requests.
+#[cfg(test)]
+pub fn stand_in_server(
+    responses: Vec<(u16, &'static str)>,
+) -> (String, thread::JoinHandle<Vec<String>>) {
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let base_url = format!("http://{}/2023", listener.local_addr().unwrap());
+    let handle = thread::spawn(move || {
+        responses
+            .into_iter()
+            .map(|(code, body)| {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let mut request = String::new();
+                let mut content_length = 0;
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    if let Some((name, value)) = line.split_once(':') {
+                        if name.eq_ignore_ascii_case("content-length") {
+                            content_length = value.trim().parse().unwrap();
+                        }
+                    }
+                    request.push_str(&line);
+                    if line == "\r\n" || line.is_empty() {
+                        break;
+                    }
+                }
+                let mut content = vec![0; content_length];
+                reader.read_exact(&mut content).unwrap();
+                request.push_str(&String::from_utf8(content).unwrap());
+                write!(
+                    stream,
+                    "HTTP/1.1 {code} Stand-in\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                    body.len()
+                )
+                .unwrap();
+                request
+            })
+            .collect()
+    });
+    (base_url, handle)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn test_client(base_url: &str, dir: &Path) -> AocClient {
+        AocClient::new(
+            base_url,
+            "abc123",
+            RateLimiter::new(dir.join(REQUEST_STAMP_FILE), Duration::ZERO),
+        )
+    }
+
+    fn test_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("aoc2023-{name}-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_fetch_input() {
+        let dir = test_dir("fetch");
+        let path = dir.join("day3/input.txt");
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, "").unwrap();
+        let (base_url, server) = stand_in_server(vec![(200, "467..114..\n")]);
+        let client = test_client(&base_url, &dir);
+        assert!(fetch_input(&client, 3, &path).unwrap());
+        assert!(!fetch_input(&client, 3, &path).unwrap());
+        assert_eq!(fs::read_to_string(&path).unwrap(), "467..114..\n");
+        let requests = server.join().unwrap();
+        assert_eq!(requests.len(), 1);
+        assert!(requests[0].starts_with("GET /2023/day/3/input "));
+        assert!(requests[0].contains("Cookie: session=abc123\r\n"));
+        assert!(requests[0].contains(&format!("User-Agent: {}\r\n", user_agent())));
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_fetch_errors() {
+        let dir = test_dir("fetch-errors");
+        let (base_url, server) = stand_in_server(vec![
+            (
+                400,
+                "Puzzle inputs differ by user.  Please log in to get your puzzle input.",
+            ),
+            (
+                404,
+                "Please don't repeatedly request this endpoint before it unlocks!",
+            ),
+            (500, "Oops"),
+        ]);
+        let client = test_client(&base_url, &dir);
+        assert_eq!(client.get_input(1), Err(AocError::SessionExpired));
+        assert_eq!(client.get_input(25), Err(AocError::Locked { day: 25 }));
+        assert_eq!(
+            client.get_input(2),
+            Err(AocError::Status {
+                code: 500,
+                body: "Oops".into()
+            })
+        );
+        server.join().unwrap();
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_rate_limiter() {
+        let dir = test_dir("rate-limit");
+        let limiter = RateLimiter::new(dir.join(REQUEST_STAMP_FILE), Duration::from_millis(50));
+        let start = std::time::Instant::now();
+        limiter.wait().unwrap();
+        limiter.wait().unwrap();
+        assert!(start.elapsed() >= Duration::from_millis(40));
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,33 @@
+use std::{ops::RangeInclusive, path::Path};
+
+use anyhow::Result;
+
+use super::{
+    client::{fetch_input, ServerArgs},
+    has_input, input_path,
+};
+
+/// Download the inputs of `days` which are not cached yet.
+/// Returns `false` if any input could not be fetched.
+pub fn run_fetch(days: RangeInclusive<usize>, server: &ServerArgs) -> Result<bool> {
+    let (cached, missing): (Vec<_>, Vec<_>) = days.partition(|&day| has_input(input_path(day)));
+    for day in cached {
+        println!("Day {day}: cached in {}", input_path(day));
+    }
+    if missing.is_empty() {
+        return Ok(true);
+    }
+    let client = server.client()?;
+    let mut success = true;
+    for day in missing {
+        let path = input_path(day);
+        match fetch_input(&client, day, Path::new(&path)) {
+            Ok(_) => println!("Day {day}: fetched {path}"),
+            Err(err) => {
+                success = false;
+                eprintln!("Day {day}: {err:#}");
+            }
+        }
+    }
+    Ok(success)
+}
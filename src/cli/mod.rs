@@ -0,0 +1,130 @@
+use std::{
+    fs::{self, File},
+    io::{self, BufRead},
+    ops::RangeInclusive,
+    path::Path,
+};
+
+use puzzle::{Answer, Assumption, DayPuzzle, PuzzleInput, TimedSolution, TimedSolutionFn};
+
+pub mod all;
+pub mod client;
+pub mod fetch;
+pub mod new;
+pub mod submit;
+pub mod verify;
+
+/// Path of the conventional puzzle input of a day
+pub fn input_path(day: usize) -> String {
+    format!("src/day{day}/input.txt")
+}
+
+/// Whether `path` holds an input which is not just an empty placeholder
+pub fn has_input(path: impl AsRef<Path>) -> bool {
+    fs::metadata(path).is_ok_and(|meta| meta.len() > 0)
+}
+
+/// Open the conventional input of a day.
+/// Returns `None` if the day has no input file or only an empty placeholder.
+fn open_input(day: usize) -> Result<Option<PuzzleInput>, String> {
+    let path = input_path(day);
+    if !has_input(&path) {
+        return Ok(None);
+    }
+    match File::open(path) {
+        Ok(file) => Ok(Some(PuzzleInput::FileLines(
+            io::BufReader::new(file).lines(),
+        ))),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(err) => Err(format!("failed opening puzzle input! {err}")),
+    }
+}
+
+/// Solve both parts on the conventional input of a day.
+/// Returns `None` if the day has no input file or only an empty placeholder.
+pub fn solve_day(
+    day: usize,
+    solve_timed: TimedSolutionFn,
+) -> Result<Option<TimedSolution>, String> {
+    let Some(lines) = open_input(day)? else {
+        return Ok(None);
+    };
+    solve_timed(lines)
+        .map(Some)
+        .map_err(|err| format!("invalid puzzle input! {err}"))
+}
+
+/// Solve only `part` of a puzzle on the conventional input of its day with default parameters.
+/// Returns `None` if the day has no input file or only an empty placeholder.
+pub fn solve_day_part(puzzle: &DayPuzzle, part: u16) -> Result<Option<Answer>, String> {
+    let params = puzzle.params(part, &[]).map_err(|err| err.to_string())?;
+    let Some(lines) = open_input(puzzle.day)? else {
+        return Ok(None);
+    };
+    (puzzle.solve)(lines, part == 2, &params)
+        .map(Some)
+        .map_err(|err| format!("invalid puzzle input! {err}"))
+}
+
+/// Print whether each assumption holds.
+/// Returns `false` if any assumption does not hold.
+pub fn report_assumptions(day: usize, assumptions: &[Assumption]) -> bool {
+    if assumptions.is_empty() {
+        println!("Day {day} makes no unstated assumptions about its input");
+    }
+    for assumption in assumptions {
+        let verdict = if assumption.holds { "holds" } else { "FAILS" };
+        println!("{verdict}: {}", assumption.description);
+    }
+    assumptions.iter().all(|assumption| assumption.holds)
+}
+
+/// Parse a single day (`5`) or a range of days (`3..=12`, `3..12`, `10..`, `..=4`)
+pub fn parse_day_range(text: &str) -> Result<RangeInclusive<usize>, String> {
+    let parse_day = |day: &str| {
+        day.trim()
+            .parse::<usize>()
+            .map_err(|_| format!("invalid day '{day}'"))
+    };
+    let range = if let Some((start, end)) = text.split_once("..") {
+        let start = if start.is_empty() {
+            1
+        } else {
+            parse_day(start)?
+        };
+        let end = if let Some(end) = end.strip_prefix('=') {
+            parse_day(end)?
+        } else if end.is_empty() {
+            25
+        } else {
+            parse_day(end)?
+                .checked_sub(1)
+                .ok_or_else(|| "empty day range".to_string())?
+        };
+        start..=end
+    } else {
+        let day = parse_day(text)?;
+        day..=day
+    };
+    if range.is_empty() {
+        Err(format!("empty day range '{text}'"))
+    } else {
+        Ok(range)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_day_range() {
+        assert_eq!(parse_day_range("5"), Ok(5..=5));
+        assert_eq!(parse_day_range("3..=12"), Ok(3..=12));
+        assert_eq!(parse_day_range("3..12"), Ok(3..=11));
+        assert_eq!(parse_day_range("10.."), Ok(10..=25));
+        assert_eq!(parse_day_range("..=4"), Ok(1..=4));
+        assert!(parse_day_range("12..3").is_err());
+        assert!(parse_day_range("x..3").is_err());
+    }
+}
@@ -0,0 +1,169 @@
+use std::{fs, path::Path};
+
+use anyhow::{anyhow, bail, Context, Result};
+
+use super::input_path;
+
+const DAY_TEMPLATE: &str = include_str!("day_template.rs.txt");
+
+const REGISTRY_START: &str = "register_days! {$\n";
+
+/// Example input and expected answers used for the generated test
+#[derive(Debug, Default)]
+pub struct Example {
+    pub input: String,
+    pub answers: [Option<String>; 2],
+}
+
+/// Quote `text` as a raw string literal which may contain `"`
+fn raw_string_literal(text: &str) -> String {
+    let hashes = (0..)
+        .map(|n| "#".repeat(n))
+        .find(|hashes| !text.contains(&format!("\"{hashes}")))
+        .unwrap();
+    format!("r{hashes}\"{text}\"{hashes}")
+}
+
+/// Render the module of `day` from the day template
+pub fn render_day_module(day: usize, title: &str, example: Option<&Example>) -> String {
+    let (ignore, input, asserts) = match example {
+        Some(example) => {
+            let asserts = example
+                .answers
+                .iter()
+                .zip([false, true])
+                .filter_map(|(answer, part2)| {
+                    let answer = answer.as_deref()?;
+                    let part = if part2 { 2 } else { 1 };
+                    Some(if answer.parse::<usize>().is_ok() {
+                        format!("        assert_eq!(part{part}(&lines), {answer});")
+                    } else {
+                        format!(
+                            "        assert_eq!(Day{day}::part{part}(&lines, &Params::default()), {answer:?}.into());"
+                        )
+                    })
+                })
+                .collect::<Vec<_>>();
+            ("", example.input.trim_end_matches('\n'), asserts.join("\n"))
+        }
+        None => (
+            "\n    #[ignore = \"missing example input\"]",
+            "",
+            String::new(),
+        ),
+    };
+    DAY_TEMPLATE
+        .replace("{{day}}", &day.to_string())
+        .replace("{{doc_title}}", title)
+        .replace("{{title}}", &title.replace('"', "\\\""))
+        .replace("{{ignore}}", ignore)
+        .replace("{{example}}", &raw_string_literal(input))
+        .replace("{{asserts}}", &asserts)
+        .replace("\n\n    }\n}\n", "\n    }\n}\n")
+}
+
+/// Insert `day` into the `register_days!` list of `lib_rs` keeping days ascending
+pub fn register_day(lib_rs: &str, day: usize) -> Result<String> {
+    let start = lib_rs
+        .find(REGISTRY_START)
+        .ok_or_else(|| anyhow!("Missing day registry in lib.rs"))?
+        + REGISTRY_START.len();
+    let end = start
+        + lib_rs[start..]
+            .find('}')
+            .ok_or_else(|| anyhow!("Unterminated day registry in lib.rs"))?;
+    let mut offset = start;
+    for entry in lib_rs[start..end].split_inclusive('\n') {
+        let registered: usize = entry
+            .split_once("=>")
+            .and_then(|(day, _)| day.trim().parse().ok())
+            .ok_or_else(|| anyhow!("Invalid day registry entry '{}'", entry.trim()))?;
+        if registered == day {
+            bail!("Day {day} is already registered");
+        }
+        if registered > day {
+            break;
+        }
+        offset += entry.len();
+    }
+    Ok(format!(
+        "{}    {day} => day{day}::Day{day},\n{}",
+        &lib_rs[..offset],
+        &lib_rs[offset..]
+    ))
+}
+
+/// Create the module of `day` with an empty input placeholder and register it
+pub fn run_new(day: usize, title: &str, example: Option<Example>) -> Result<()> {
+    if !(1..=25).contains(&day) {
+        bail!("Day {day} is not an advent day");
+    }
+    let module_dir = format!("src/day{day}");
+    if Path::new(&module_dir).exists() {
+        bail!("Module {module_dir} already exists");
+    }
+    let lib_path = "src/lib.rs";
+    let lib_rs =
+        fs::read_to_string(lib_path).with_context(|| format!("Failed reading {lib_path}"))?;
+    let lib_rs = register_day(&lib_rs, day)?;
+
+    fs::create_dir_all(&module_dir)?;
+    fs::write(
+        format!("{module_dir}/mod.rs"),
+        render_day_module(day, title, example.as_ref()),
+    )?;
+    fs::write(input_path(day), "")?;
+    fs::write(lib_path, lib_rs)?;
+    println!("Created {module_dir} and registered day {day} in {lib_path}");
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_register_day() {
+        let lib_rs =
+            "mod error;\n\nregister_days! {$\n    1 => day1::Day1,\n    3 => day3::Day3,\n}\n";
+        assert_eq!(
+            register_day(lib_rs, 2).unwrap(),
+            "mod error;\n\nregister_days! {$\n    1 => day1::Day1,\n    2 => day2::Day2,\n    3 => day3::Day3,\n}\n"
+        );
+        assert!(register_day(lib_rs, 4)
+            .unwrap()
+            .ends_with("    3 => day3::Day3,\n    4 => day4::Day4,\n}\n"));
+        assert!(register_day(lib_rs, 3).is_err());
+    }
+
+    #[test]
+    fn test_render_day_module() {
+        let example = Example {
+            input: "say \"hi\"\n".into(),
+            answers: [Some("42".into()), Some("abc".into())],
+        };
+        let module = render_day_module(24, "Never Tell Me The Odds", Some(&example));
+        assert!(module.contains("pub struct Day24;"));
+        assert!(module.contains("const TITLE: &'static str = \"Never Tell Me The Odds\";"));
+        assert!(module.contains("let test_input = r#\"say \"hi\"\"#;"));
+        assert!(module.starts_with("//! Day 24: Never Tell Me The Odds\n"));
+        assert!(module.contains("assert_eq!(part1(&lines), 42);"));
+        assert!(module
+            .contains("assert_eq!(Day24::part2(&lines, &Params::default()), \"abc\".into());"));
+        assert!(!module.contains("#[ignore"));
+
+        // Negative answers do not fit the `usize` of the typed parts
+        let example = Example {
+            input: "1\n".into(),
+            answers: [Some("-3".into()), None],
+        };
+        let module = render_day_module(9, "Mirage Maintenance", Some(&example));
+        assert!(
+            module.contains("assert_eq!(Day9::part1(&lines, &Params::default()), \"-3\".into());")
+        );
+
+        let module = render_day_module(24, "Odds", None);
+        assert!(module.contains("#[ignore = \"missing example input\"]"));
+        assert!(module.contains("let test_input = r\"\";\n"));
+    }
+}
@@ -0,0 +1,308 @@
+use std::{
+    fmt::Display,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+
+use puzzle::{day_puzzle, Answer};
+
+use super::{client::AocClient, input_path, solve_day_part};
+
+/// Response of the puzzle server to a submitted answer
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Outcome {
+    Correct,
+    Wrong,
+    TooHigh,
+    TooLow,
+    RateLimited,
+    WrongLevel,
+}
+
+impl Outcome {
+    /// Parse the article text of the answer response page
+    pub fn parse(html: &str) -> Option<Self> {
+        if html.contains("That's the right answer") {
+            Some(Outcome::Correct)
+        } else if html.contains("That's not the right answer") {
+            Some(if html.contains("your answer is too high") {
+                Outcome::TooHigh
+            } else if html.contains("your answer is too low") {
+                Outcome::TooLow
+            } else {
+                Outcome::Wrong
+            })
+        } else if html.contains("You gave an answer too recently") {
+            Some(Outcome::RateLimited)
+        } else if html.contains("You don't seem to be solving the right level") {
+            Some(Outcome::WrongLevel)
+        } else {
+            None
+        }
+    }
+
+    fn is_wrong(self) -> bool {
+        matches!(self, Outcome::Wrong | Outcome::TooHigh | Outcome::TooLow)
+    }
+}
+
+impl Display for Outcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Outcome::Correct => "correct",
+            Outcome::Wrong => "wrong",
+            Outcome::TooHigh => "too high",
+            Outcome::TooLow => "too low",
+            Outcome::RateLimited => "rate limited, try again later",
+            Outcome::WrongLevel => "wrong level, part may be locked or solved already",
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Submission {
+    pub day: usize,
+    pub part: u16,
+    pub answer: Answer,
+    pub outcome: Outcome,
+}
+
+/// Local record of all submitted answers
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct History {
+    #[serde(default, rename = "submission")]
+    pub submissions: Vec<Submission>,
+}
+
+impl History {
+    pub fn load(path: &Path) -> Result<Self> {
+        match fs::read_to_string(path) {
+            Ok(text) => {
+                toml::from_str(&text).with_context(|| format!("Invalid history file {path:?}"))
+            }
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(err) => Err(err).with_context(|| format!("Failed reading {path:?}")),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        fs::write(path, toml::to_string(self)?).with_context(|| format!("Failed writing {path:?}"))
+    }
+
+    fn submissions_of(&self, day: usize, part: u16) -> impl Iterator<Item = &Submission> {
+        self.submissions
+            .iter()
+            .filter(move |s| s.day == day && s.part == part)
+    }
+
+    /// Largest answer known to be too low and smallest answer known to be too high
+    pub fn bounds(&self, day: usize, part: u16) -> (Option<&Answer>, Option<&Answer>) {
+        let numeric = |outcome| {
+            self.submissions_of(day, part)
+                .filter(move |s| s.outcome == outcome && s.answer.is_number())
+                .map(|s| &s.answer)
+        };
+        (
+            numeric(Outcome::TooLow).max(),
+            numeric(Outcome::TooHigh).min(),
+        )
+    }
+
+    /// Reason why `answer` should not be submitted according to previous submissions
+    pub fn refusal(&self, day: usize, part: u16, answer: &Answer) -> Option<String> {
+        if !answer.is_solved() {
+            return Some(format!("no answer to submit, {answer}"));
+        }
+        if let Some(correct) = self
+            .submissions_of(day, part)
+            .find(|s| s.outcome == Outcome::Correct)
+        {
+            return Some(format!("already solved with answer {}", correct.answer));
+        }
+        if let Some(wrong) = self
+            .submissions_of(day, part)
+            .find(|s| s.answer == *answer && s.outcome.is_wrong())
+        {
+            return Some(format!(
+                "answer {answer} is known to be {:?}",
+                wrong.outcome
+            ));
+        }
+        if !answer.is_number() {
+            return None;
+        }
+        match self.bounds(day, part) {
+            (Some(low), _) if answer <= low => {
+                Some(format!("answer {answer} is not above too low answer {low}"))
+            }
+            (_, Some(high)) if answer >= high => Some(format!(
+                "answer {answer} is not below too high answer {high}"
+            )),
+            _ => None,
+        }
+    }
+}
+
+/// Submit `answer` unless the history already rules it out and record the outcome
+pub fn submit_answer(
+    client: &AocClient,
+    history: &mut History,
+    day: usize,
+    part: u16,
+    answer: &Answer,
+) -> Result<Outcome> {
+    if let Some(reason) = history.refusal(day, part, answer) {
+        return Err(anyhow!("Refusing to submit: {reason}"));
+    }
+    let html = client.post_answer(day, part, &answer.to_string())?;
+    let outcome =
+        Outcome::parse(&html).ok_or_else(|| anyhow!("Unexpected answer response: {html}"))?;
+    if outcome != Outcome::RateLimited {
+        history.submissions.push(Submission {
+            day,
+            part,
+            answer: answer.clone(),
+            outcome,
+        });
+    }
+    Ok(outcome)
+}
+
+/// Solve `part` of `day` on its conventional input and submit the answer.
+/// Returns `true` if the answer is correct.
+pub fn run_submit(
+    day: usize,
+    part: u16,
+    client: &AocClient,
+    history_file: PathBuf,
+) -> Result<bool> {
+    let puzzle = day_puzzle(day).ok_or_else(|| anyhow!("Puzzle day {day} not found!"))?;
+    if part > puzzle.parts {
+        return Err(anyhow!("Puzzle day {day} has no part {part}"));
+    }
+    let answer = solve_day_part(&puzzle, part)
+        .map_err(|err| anyhow!("Day {day}: {err}"))?
+        .ok_or_else(|| anyhow!("Missing puzzle input {}", input_path(day)))?;
+    let mut history = History::load(&history_file)?;
+    println!("Submitting day {day} part {part}: {answer}");
+    let outcome = submit_answer(client, &mut history, day, part, &answer)?;
+    history.save(&history_file)?;
+    println!("Answer is {outcome}");
+    Ok(outcome == Outcome::Correct)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::cli::client::{stand_in_server, RateLimiter};
+    use std::time::Duration;
+
+    fn submission(answer: &str, outcome: Outcome) -> Submission {
+        Submission {
+            day: 5,
+            part: 1,
+            answer: answer.into(),
+            outcome,
+        }
+    }
+
+    #[test]
+    fn test_parse_outcome() {
+        let page = |text| format!("<main>\n<article><p>{text}</p></article>\n</main>");
+        assert_eq!(
+            Outcome::parse(&page(
+                "That's the right answer!  You are one gold star closer."
+            )),
+            Some(Outcome::Correct)
+        );
+        assert_eq!(
+            Outcome::parse(&page(
+                "That's not the right answer; your answer is too high."
+            )),
+            Some(Outcome::TooHigh)
+        );
+        assert_eq!(
+            Outcome::parse(&page(
+                "That's not the right answer; your answer is too low."
+            )),
+            Some(Outcome::TooLow)
+        );
+        assert_eq!(
+            Outcome::parse(&page("That's not the right answer.  If you're stuck, ...")),
+            Some(Outcome::Wrong)
+        );
+        assert_eq!(
+            Outcome::parse(&page("You gave an answer too recently; you have to wait.")),
+            Some(Outcome::RateLimited)
+        );
+        assert_eq!(
+            Outcome::parse(&page("You don't seem to be solving the right level.")),
+            Some(Outcome::WrongLevel)
+        );
+        assert_eq!(Outcome::parse(&page("Hello")), None);
+    }
+
+    #[test]
+    fn test_history_refusal() {
+        let history = History {
+            submissions: vec![
+                submission("10", Outcome::TooLow),
+                submission("50", Outcome::TooHigh),
+                submission("20", Outcome::TooLow),
+                submission("30", Outcome::Wrong),
+            ],
+        };
+        assert_eq!(
+            history.bounds(5, 1),
+            (Some(&Answer::Integer(20)), Some(&Answer::Integer(50)))
+        );
+        assert_eq!(history.bounds(5, 2), (None, None));
+        assert!(history.refusal(5, 1, &"30".into()).is_some());
+        assert!(history.refusal(5, 1, &"20".into()).is_some());
+        assert!(history.refusal(5, 1, &"51".into()).is_some());
+        assert_eq!(history.refusal(5, 1, &"21".into()), None);
+        assert_eq!(history.refusal(5, 2, &"30".into()), None);
+        assert_eq!(
+            history.refusal(5, 2, &Answer::Unsolved("no path".into())),
+            Some("no answer to submit, unsolved: no path".into())
+        );
+
+        let text = toml::to_string(&history).unwrap();
+        assert_eq!(toml::from_str::<History>(&text).unwrap(), history);
+    }
+
+    #[test]
+    fn test_submit_answer() {
+        let dir = std::env::temp_dir().join(format!("aoc2023-submit-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let (base_url, server) =
+            stand_in_server(vec![
+            (200, "<article><p>That's not the right answer; your answer is too low.</p></article>"),
+            (200, "<article><p>That's the right answer!</p></article>"),
+        ]);
+        let client = AocClient::new(
+            &base_url,
+            "abc123",
+            RateLimiter::new(dir.join("stamp"), Duration::ZERO),
+        );
+        let mut history = History::default();
+        assert_eq!(
+            submit_answer(&client, &mut history, 5, 1, &"7".into()).unwrap(),
+            Outcome::TooLow
+        );
+        assert!(submit_answer(&client, &mut history, 5, 1, &"6".into()).is_err());
+        assert_eq!(
+            submit_answer(&client, &mut history, 5, 1, &"35".into()).unwrap(),
+            Outcome::Correct
+        );
+        assert_eq!(history.submissions.len(), 2);
+        let requests = server.join().unwrap();
+        assert!(requests[0].starts_with("POST /2023/day/5/answer "));
+        assert!(requests[0].ends_with("level=1&answer=7"));
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
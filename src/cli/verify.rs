@@ -0,0 +1,199 @@
+use std::{
+    collections::BTreeMap,
+    fmt::Write,
+    fs, io,
+    ops::RangeInclusive,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+
+use puzzle::{Answer, DAYS};
+
+use super::{input_path, solve_day};
+
+/// Recorded answers of both parts of a day
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DayAnswers {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub part1: Option<Answer>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub part2: Option<Answer>,
+}
+
+/// Known-correct answers stored as `[dayN]` tables of a TOML file
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Answers {
+    pub days: BTreeMap<usize, DayAnswers>,
+}
+
+impl Answers {
+    /// Parse answers from TOML with `[dayN]` tables containing `part1` and `part2`
+    pub fn parse(text: &str) -> Result<Self> {
+        let tables: BTreeMap<String, DayAnswers> = toml::from_str(text)?;
+        let days = tables
+            .into_iter()
+            .map(|(key, answers)| {
+                let day = key
+                    .strip_prefix("day")
+                    .and_then(|day| day.parse().ok())
+                    .ok_or_else(|| anyhow!("invalid table '{key}', expected 'dayN'"))?;
+                Ok((day, answers))
+            })
+            .collect::<Result<_>>()?;
+        Ok(Self { days })
+    }
+
+    /// Load answers from `path` or start empty if the file does not exist
+    pub fn load(path: &Path) -> Result<Self> {
+        match fs::read_to_string(path) {
+            Ok(text) => {
+                Self::parse(&text).with_context(|| format!("Invalid answers file {path:?}"))
+            }
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(err) => Err(err).with_context(|| format!("Failed reading {path:?}")),
+        }
+    }
+
+    /// Format answers as TOML with days in ascending order
+    pub fn to_toml(&self) -> Result<String> {
+        let mut text = String::new();
+        for (day, answers) in &self.days {
+            if !text.is_empty() {
+                text.push('\n');
+            }
+            writeln!(text, "[day{day}]")?;
+            text.push_str(&toml::to_string(answers)?);
+        }
+        Ok(text)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        fs::write(path, self.to_toml()?).with_context(|| format!("Failed writing {path:?}"))
+    }
+}
+
+/// Outcome of checking a single computed answer
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verdict {
+    Correct,
+    Mismatch { expected: Answer },
+    Unrecorded,
+}
+
+pub fn check_answer(expected: Option<&Answer>, answer: &Answer) -> Verdict {
+    match expected {
+        Some(expected) if expected == answer => Verdict::Correct,
+        Some(expected) => Verdict::Mismatch {
+            expected: expected.clone(),
+        },
+        None => Verdict::Unrecorded,
+    }
+}
+
+/// Solve every day in `days` and compare the answers against `answers_file`.
+/// With `record`, answers of parts which are not recorded yet are written to the file.
+/// Returns `false` if any answer does not match, any part is unsolved or any puzzle input is invalid.
+pub fn run_verify(
+    days: RangeInclusive<usize>,
+    answers_file: PathBuf,
+    record: bool,
+) -> Result<bool> {
+    let mut answers = Answers::load(&answers_file)?;
+    let mut success = true;
+    let mut recorded = 0;
+    for puzzle in DAYS.iter().filter(|puzzle| days.contains(&puzzle.day)) {
+        let day = puzzle.day;
+        let solution = match solve_day(day, puzzle.solve_timed) {
+            Ok(Some(solution)) => solution,
+            Ok(None) => {
+                println!("Day {day}: skipped (no input in {})", input_path(day));
+                continue;
+            }
+            Err(err) => {
+                success = false;
+                println!("Day {day}: error: {err}");
+                continue;
+            }
+        };
+        let day_answers = answers.days.entry(day).or_default();
+        for (part, computed) in solution.parts() {
+            let computed = &computed.answer;
+            if !computed.is_solved() {
+                success = false;
+                println!("Day {day} part {part}: {computed}");
+                continue;
+            }
+            let expected = if part == 2 {
+                &mut day_answers.part2
+            } else {
+                &mut day_answers.part1
+            };
+            match check_answer(expected.as_ref(), computed) {
+                Verdict::Correct => println!("Day {day} part {part}: ok"),
+                Verdict::Mismatch { expected } => {
+                    success = false;
+                    println!("Day {day} part {part}: MISMATCH expected {expected}, got {computed}");
+                }
+                Verdict::Unrecorded if record => {
+                    println!("Day {day} part {part}: recorded {computed}");
+                    *expected = Some(computed.clone());
+                    recorded += 1;
+                }
+                Verdict::Unrecorded => {
+                    println!("Day {day} part {part}: unrecorded answer {computed}")
+                }
+            }
+        }
+    }
+    if recorded > 0 {
+        answers
+            .days
+            .retain(|_, answers| *answers != DayAnswers::default());
+        answers.save(&answers_file)?;
+        println!("Recorded {recorded} answers in {answers_file:?}");
+    }
+    Ok(success)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_answers_toml() {
+        let text = "[day2]\npart1 = \"8\"\npart2 = \"2286\"\n\n[day10]\npart1 = \"4\"\n";
+        let answers = Answers::parse(text).unwrap();
+        assert_eq!(answers.days.keys().copied().collect::<Vec<_>>(), [2, 10]);
+        assert_eq!(answers.days[&10].part2, None);
+        assert_eq!(answers.days[&2].part2, Some(Answer::Integer(2286)));
+        assert_eq!(answers.to_toml().unwrap(), text);
+        assert!(Answers::parse("[two]\npart1 = \"8\"\n").is_err());
+
+        // Hand-written answers may be plain TOML integers
+        let answers = Answers::parse("[day9]\npart1 = 114\npart2 = -2\n").unwrap();
+        assert_eq!(answers.days[&9].part1, Some(Answer::Integer(114)));
+        assert_eq!(answers.days[&9].part2, Some(Answer::Signed(-2)));
+        assert_eq!(
+            answers.to_toml().unwrap(),
+            "[day9]\npart1 = \"114\"\npart2 = \"-2\"\n"
+        );
+    }
+
+    #[test]
+    fn test_check_answer() {
+        let expected = Answer::from(8_u32);
+        assert_eq!(
+            check_answer(Some(&expected), &Answer::Integer(8)),
+            Verdict::Correct
+        );
+        assert_eq!(
+            check_answer(Some(&expected), &Answer::Signed(9)),
+            Verdict::Mismatch {
+                expected: "8".into()
+            }
+        );
+        assert_eq!(check_answer(None, &"9".into()), Verdict::Unrecorded);
+    }
+}
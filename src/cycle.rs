@@ -0,0 +1,235 @@
+use std::{
+    collections::{hash_map::RandomState, HashMap},
+    hash::{BuildHasher, Hash},
+};
+
+/// Steps of a sequence of states `x(0), x(1), ...` with `x(i + 1) = step(x(i))`
+/// which first reach a repeating state and then repeat
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cycle {
+    /// First step of the cycle, i.e. the number of states before the cycle
+    pub prefix: usize,
+    pub period: usize,
+}
+
+impl Cycle {
+    /// Earliest step which has the same state as step `n`
+    pub fn reduce(&self, n: usize) -> usize {
+        if n < self.prefix {
+            n
+        } else {
+            self.prefix + (n - self.prefix) % self.period
+        }
+    }
+}
+
+/// Every state seen so far, indexed by its hash to find the first repeated state.
+/// Each state is stored once, so large states only need the memory of the sequence.
+pub struct CycleHistory<T> {
+    states: Vec<T>,
+    steps_by_hash: HashMap<u64, Vec<usize>>,
+    hasher: RandomState,
+}
+
+impl<T: Hash + Eq> CycleHistory<T> {
+    pub fn new() -> Self {
+        Self {
+            states: vec![],
+            steps_by_hash: HashMap::new(),
+            hasher: RandomState::new(),
+        }
+    }
+
+    /// Record `state` as the next step.
+    /// Returns the cycle instead if `state` has been recorded before.
+    pub fn record(&mut self, state: T) -> Option<Cycle> {
+        let steps = self
+            .steps_by_hash
+            .entry(self.hasher.hash_one(&state))
+            .or_default();
+        if let Some(&prefix) = steps.iter().find(|&&step| self.states[step] == state) {
+            return Some(Cycle {
+                prefix,
+                period: self.states.len() - prefix,
+            });
+        }
+        steps.push(self.states.len());
+        self.states.push(state);
+        None
+    }
+
+    /// Recorded state of `step`
+    pub fn get(&self, step: usize) -> Option<&T> {
+        self.states.get(step)
+    }
+
+    pub fn len(&self) -> usize {
+        self.states.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.states.is_empty()
+    }
+
+    pub fn into_states(self) -> Vec<T> {
+        self.states
+    }
+}
+
+impl<T: Hash + Eq> Default for CycleHistory<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Find the cycle of the states from `initial` by recording each state in a [`CycleHistory`]
+pub fn find_cycle_hashed<T: Hash + Eq + Clone>(initial: T, mut step: impl FnMut(&T) -> T) -> Cycle {
+    let mut history = CycleHistory::new();
+    let mut state = initial;
+    loop {
+        let next = step(&state);
+        if let Some(cycle) = history.record(state) {
+            return cycle;
+        }
+        state = next;
+    }
+}
+
+/// Find the cycle of the states from `initial` with Brent's algorithm,
+/// which keeps two states but calls `step` more often than [`find_cycle_hashed`]
+pub fn find_cycle_brent<T: PartialEq + Clone>(initial: T, mut step: impl FnMut(&T) -> T) -> Cycle {
+    // Find the period with the tortoise waiting at powers of two
+    let mut power = 1;
+    let mut period = 1;
+    let mut tortoise = initial.clone();
+    let mut hare = step(&initial);
+    while tortoise != hare {
+        if power == period {
+            tortoise = hare.clone();
+            power *= 2;
+            period = 0;
+        }
+        hare = step(&hare);
+        period += 1;
+    }
+
+    // Walk both one period apart until they meet at the start of the cycle
+    let mut tortoise = initial.clone();
+    let mut hare = initial;
+    for _ in 0..period {
+        hare = step(&hare);
+    }
+    let mut prefix = 0;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        prefix += 1;
+    }
+    Cycle { prefix, period }
+}
+
+/// Find the cycle of the states from `initial` with Floyd's algorithm
+pub fn find_cycle_floyd<T: PartialEq + Clone>(initial: T, mut step: impl FnMut(&T) -> T) -> Cycle {
+    // The hare runs twice as fast until it meets the tortoise within the cycle
+    let mut tortoise = step(&initial);
+    let mut hare = step(&tortoise);
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        hare = step(&hare);
+    }
+
+    // The distance from the start to the cycle equals the distance from there
+    let mut tortoise = initial;
+    let mut prefix = 0;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        prefix += 1;
+    }
+
+    let mut hare = step(&tortoise);
+    let mut period = 1;
+    while tortoise != hare {
+        hare = step(&hare);
+        period += 1;
+    }
+    Cycle { prefix, period }
+}
+
+/// State after `n` steps from `initial`.
+/// Once a state repeats, the remaining whole periods are skipped.
+pub fn fast_forward<T: Hash + Eq + Clone>(
+    initial: T,
+    mut step: impl FnMut(&T) -> T,
+    n: usize,
+) -> T {
+    let mut history = CycleHistory::new();
+    let mut state = initial;
+    for _ in 0..n {
+        let next = step(&state);
+        if let Some(cycle) = history.record(state) {
+            return history.into_states().swap_remove(cycle.reduce(n));
+        }
+        state = next;
+    }
+    state
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// `x -> x^2 + 1 (mod 255)` from 3 enters a cycle of length 6 after 2 steps
+    fn square_plus_one(x: &u64) -> u64 {
+        (x * x + 1) % 255
+    }
+
+    #[test]
+    fn test_find_cycle() {
+        let expected = Cycle {
+            prefix: 2,
+            period: 6,
+        };
+        assert_eq!(find_cycle_hashed(3, square_plus_one), expected);
+        assert_eq!(find_cycle_brent(3, square_plus_one), expected);
+        assert_eq!(find_cycle_floyd(3, square_plus_one), expected);
+
+        let only_cycle = Cycle {
+            prefix: 0,
+            period: 5,
+        };
+        let add_two = |x: &u8| (x + 2) % 5;
+        assert_eq!(find_cycle_hashed(0, add_two), only_cycle);
+        assert_eq!(find_cycle_brent(0, add_two), only_cycle);
+        assert_eq!(find_cycle_floyd(0, add_two), only_cycle);
+
+        let fixed_point = Cycle {
+            prefix: 3,
+            period: 1,
+        };
+        let halve = |x: &u8| x / 2;
+        assert_eq!(find_cycle_hashed(7, halve), fixed_point);
+        assert_eq!(find_cycle_brent(7, halve), fixed_point);
+        assert_eq!(find_cycle_floyd(7, halve), fixed_point);
+    }
+
+    #[test]
+    fn test_fast_forward() {
+        let steps = itertools::iterate(3, square_plus_one)
+            .take(20)
+            .collect::<Vec<_>>();
+        for (n, &expected) in steps.iter().enumerate() {
+            assert_eq!(fast_forward(3, square_plus_one, n), expected);
+        }
+        assert_eq!(fast_forward(3, square_plus_one, 1_000_000_000), steps[4]);
+        assert_eq!(
+            Cycle {
+                prefix: 2,
+                period: 6
+            }
+            .reduce(1_000_000_000),
+            4
+        );
+    }
+}
@@ -1,4 +1,8 @@
-use crate::PuzzleInput;
+//! Day 1: Trebuchet?!
+//!
+//! Each line of the calibration document is kept as is.
+
+use crate::{Answer, Params, ParseError, Puzzle, PuzzleInput};
 
 fn labelstart_to_num(label_start: &str, part2: bool) -> Option<u32> {
     [
@@ -10,14 +14,15 @@ fn labelstart_to_num(label_start: &str, part2: bool) -> Option<u32> {
     .or_else(|| label_start.chars().next().and_then(|c| c.to_digit(10)))
 }
 
-fn run(input: PuzzleInput, part2: bool) -> u32 {
-    input
+fn run(lines: &[String], part2: bool) -> u32 {
+    lines
+        .iter()
         .map(|line| {
             let mut nums = line
                 .char_indices()
                 .filter_map(|(i, _)| labelstart_to_num(&line[i..], part2));
             let (first, last) = if let Some(first_num) = nums.next() {
-                (first_num, nums.last().unwrap_or(first_num))
+                (first_num, nums.next_back().unwrap_or(first_num))
             } else {
                 (0, 0)
             };
@@ -26,40 +31,68 @@ fn run(input: PuzzleInput, part2: bool) -> u32 {
         .sum()
 }
 
-pub fn solution(input: PuzzleInput, part2: bool) -> String {
-    run(input, part2).to_string()
+/// Sum of the calibration values made of the first and last digit of each line
+pub fn part1(lines: &[String]) -> u32 {
+    run(lines, false)
+}
+
+/// Sum of the calibration values where digits may also be spelled out
+pub fn part2(lines: &[String]) -> u32 {
+    run(lines, true)
+}
+
+pub struct Day1;
+
+impl Puzzle for Day1 {
+    type Input = Vec<String>;
+
+    const TITLE: &'static str = "Trebuchet?!";
+
+    fn parse(input: PuzzleInput) -> Result<Self::Input, ParseError> {
+        Ok(input.collect())
+    }
+
+    fn part1(lines: &Self::Input, _params: &Params) -> Answer {
+        part1(lines).into()
+    }
+
+    fn part2(lines: &Self::Input, _params: &Params) -> Answer {
+        part2(lines).into()
+    }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
 
+    fn run_str(input: &'static str, part2: bool) -> u32 {
+        run(&Day1::parse(input.into()).unwrap(), part2)
+    }
+
     #[test]
     fn test_run() {
         assert_eq!(
-            run(
+            run_str(
                 r"1abc2
 pqr3stu8vwx
 a1b2c3d4e5f
-treb7uchet"
-                    .into(),
+treb7uchet",
                 false
             ),
             142
         );
-        assert_eq!(run("soneighttwo39ktl132".into(), true), 12);
-        assert_eq!(run("oneight".into(), true), 18);
-        assert_eq!(run("oneightwo".into(), true), 12);
+        assert_eq!(run_str("soneighttwo39ktl132", true), 12);
+        assert_eq!(run_str("oneight", true), 18);
+        assert_eq!(run_str("oneightwo", true), 12);
         assert_eq!(
-            run(
+            run_str(
                 r"two1nine
 eightwothree
 abcone2threexyz
 xtwone3four
 4nineeightseven2
 zoneight234
-7pqrstsixteen"
-                    .into(),
+7pqrstsixteen",
                 true
             ),
             281
@@ -1,72 +1,35 @@
-use std::fmt::Display;
-
-use bitvec::prelude::*;
-
-use crate::PuzzleInput;
-
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-enum Direction {
-    Right = 0,
-    Up,
-    Left,
-    Down,
-}
-
-const DIRS: [Direction; 4] = [
-    Direction::Right,
-    Direction::Up,
-    Direction::Left,
-    Direction::Down,
-];
+//! Day 10: Pipe Maze
+//!
+//! The [`PipeGrid`] holds the pipe connections of each tile and the start tile.
 
-impl Direction {
-    fn left(&self) -> Direction {
-        self.right().inverse()
-    }
-
-    fn right(&self) -> Direction {
-        match self {
-            Direction::Right => Direction::Down,
-            Direction::Up => Direction::Right,
-            Direction::Left => Direction::Up,
-            Direction::Down => Direction::Left,
-        }
-    }
-
-    fn inverse(&self) -> Direction {
-        match self {
-            Direction::Right => Direction::Left,
-            Direction::Up => Direction::Down,
-            Direction::Left => Direction::Right,
-            Direction::Down => Direction::Up,
-        }
-    }
+use std::fmt::Display;
 
-    fn next(&self) -> impl Iterator<Item = Direction> {
-        let inverse_direction = self.inverse();
-        DIRS.into_iter().filter(move |d| inverse_direction != *d)
-    }
-}
+use crate::{
+    Answer, BitGrid, Direction, Grid, GridShape, Params, ParseError, Position, Puzzle, PuzzleInput,
+};
 
-type Pos = usize;
+/// Connected directions of a pipe tile indexed by `Direction`
+type Pipe = [bool; 4];
 
-struct PipeGrid {
-    directions: BitVec,
-    width: usize,
-    start_pos: usize,
+pub struct PipeGrid {
+    pipes: Grid<Pipe>,
+    start_pos: Position,
 }
 
 struct PipeGridWalk<'a> {
     grid: &'a PipeGrid,
     walk_direction: Direction,
-    pos: Pos,
+    pos: Position,
 }
 
 impl<'a> Iterator for PipeGridWalk<'a> {
-    type Item = (Pos, Direction);
+    type Item = (Position, Direction);
 
     fn next(&mut self) -> Option<Self::Item> {
-        self.pos = self.grid.move_pos(self.pos, self.walk_direction);
+        self.pos = self
+            .grid
+            .step(self.pos, self.walk_direction)
+            .expect("pipe loop should stay inside the grid");
         if self.pos == self.grid.start_pos {
             None
         } else {
@@ -75,20 +38,15 @@ impl<'a> Iterator for PipeGridWalk<'a> {
         }
     }
 }
-fn pipe_char_to_directions(pipe_char: char) -> [bool; 4] {
-    let f = false;
-    match pipe_char {
-        'L' => [true, true, f, f],
-        'J' => [f, true, true, f],
-        '7' => [f, f, true, true],
-        '-' => [true, f, true, f],
-        '|' => [f, true, f, true],
-        'F' => [true, f, f, true],
-        _ => [f, f, f, f],
+fn pipe_char_to_directions(pipe_char: char) -> Pipe {
+    let mut pipe = [false; 4];
+    for d in Direction::pipe_ends(pipe_char).into_iter().flatten() {
+        pipe[d.index()] = true;
     }
+    pipe
 }
 
-fn directions_to_pipe_char(has_directions: [bool; 4]) -> char {
+fn directions_to_pipe_char(has_directions: Pipe) -> char {
     match has_directions {
         [true, true, _, _] => '┗',
         [_, true, true, _] => '┛',
@@ -109,9 +67,10 @@ fn direction_to_walk_char(dir: Direction) -> char {
 }
 impl<'a> Display for PipeGridWalk<'a> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        for row in 0..self.grid.width {
-            for col in 0..self.grid.width {
-                let pos = row * self.grid.width + col;
+        let pipes = &self.grid.pipes;
+        for row in 0..pipes.height() {
+            for col in 0..pipes.width() {
+                let pos = pipes.position(row, col);
                 let cur_pos = pos == self.pos;
                 write!(
                     f,
@@ -121,7 +80,7 @@ impl<'a> Display for PipeGridWalk<'a> {
                     } else if pos == self.grid.start_pos {
                         '○'
                     } else {
-                        directions_to_pipe_char(DIRS.map(|d| self.grid.has_direction(pos, d)))
+                        directions_to_pipe_char(pipes[pos])
                     }
                 )?;
             }
@@ -132,107 +91,79 @@ impl<'a> Display for PipeGridWalk<'a> {
 }
 
 impl PipeGrid {
-    fn parse(mut input: PuzzleInput) -> Self {
-        let first_line = input.next().unwrap();
-        let width = first_line.chars().count();
-        // Parse a square grid into a `(row, col, direction) => bool` collection
-        let bitcount = 4 * width * width;
-        let mut directions = BitVec::with_capacity(bitcount);
-        let mut start_pos = 0;
-        for (row, line) in [first_line].into_iter().chain(input).enumerate() {
-            if start_pos == 0 {
-                if let Some(col) = line.find('S') {
-                    start_pos = row * width + col;
-                }
+    fn parse(input: PuzzleInput) -> Result<Self, ParseError> {
+        let mut start_pos = None;
+        let mut pos = 0;
+        // ('I' and 'O' mark inside and outside ground tiles in the puzzle examples)
+        let pipes = Grid::parse(input.enumerate(), "|-LJ7F.SIO", |c| {
+            if c == 'S' {
+                start_pos.get_or_insert(pos);
             }
-            directions.extend(line.chars().flat_map(pipe_char_to_directions))
-        }
-        directions.extend(bitvec!(0; bitcount - directions.len()));
-        Self {
-            directions,
-            start_pos,
-            width,
-        }
+            pos += 1;
+            pipe_char_to_directions(c)
+        })?;
+        Ok(Self {
+            pipes,
+            start_pos: start_pos.ok_or_else(|| ParseError::new(0, 0, "'S' start tile"))?,
+        })
     }
 
-    fn output_direction(&self, pos: Pos, walk_direction: Direction) -> Direction {
+    fn output_direction(&self, pos: Position, walk_direction: Direction) -> Direction {
         walk_direction
-            .next()
+            .ahead()
             .find(|d| self.has_direction(pos, *d))
             .expect("pos should be connected")
     }
 
-    fn has_direction(&self, pos: Pos, direction: Direction) -> bool {
-        self.directions[4 * pos + direction as usize]
+    fn has_direction(&self, pos: Position, direction: Direction) -> bool {
+        self.pipes[pos][direction.index()]
     }
 
-    fn move_pos(&self, pos: Pos, move_direction: Direction) -> Pos {
-        match move_direction {
-            Direction::Right => pos + 1,
-            Direction::Up => pos - self.width,
-            Direction::Left => pos - 1,
-            Direction::Down => pos + self.width,
-        }
-    }
-
-    fn row_col(&self, pos: Pos) -> (usize, usize) {
-        num_integer::div_rem(pos, self.width)
+    fn step(&self, pos: Position, direction: Direction) -> Option<Position> {
+        self.pipes.step(pos, direction)
     }
 
     fn walk(&self) -> PipeGridWalk<'_> {
         PipeGridWalk {
             grid: self,
-            walk_direction: DIRS
+            walk_direction: Direction::ALL
                 .into_iter()
-                .find(|d| self.has_direction(self.move_pos(self.start_pos, *d), d.inverse()))
+                .find(|d| {
+                    self.step(self.start_pos, *d)
+                        .is_some_and(|pos| self.has_direction(pos, d.reverse()))
+                })
                 .expect("start should be connected"),
             pos: self.start_pos,
         }
     }
-
-    fn move_possible(&self, pos: Pos, dir: Direction) -> bool {
-        let (row, col) = self.row_col(pos);
-        match dir {
-            Direction::Right => col < self.width - 1,
-            Direction::Up => row > 0,
-            Direction::Left => col > 0,
-            Direction::Down => row < self.width - 1,
-        }
-    }
 }
 
-fn flood_fill_empty(field: &mut BitVec, start_pos: Pos, empty: &BitVec, grid: &PipeGrid) {
+fn flood_fill_empty(field: &mut BitGrid, start_pos: Position, empty: &BitGrid, grid: &PipeGrid) {
     let mut next_positions = vec![start_pos];
     while !next_positions.is_empty() {
         next_positions = next_positions
             .into_iter()
             .filter(|pos| {
                 let p = *pos;
-                let is_new = empty[p] && !field[p];
+                let is_new = empty.get(p) && !field.get(p);
                 if is_new {
                     field.set(p, true);
                 }
                 is_new
             })
             .flat_map(|pos| {
-                DIRS.into_iter().filter_map(move |dir| {
-                    if grid.move_possible(pos, dir) {
-                        Some(grid.move_pos(pos, dir))
-                    } else {
-                        None
-                    }
-                })
+                Direction::ALL
+                    .into_iter()
+                    .filter_map(move |dir| grid.step(pos, dir))
             })
             .collect();
     }
 }
-
-fn run(input: PuzzleInput, part2: bool) -> usize {
-    let grid = PipeGrid::parse(input);
+fn run(grid: &PipeGrid, part2: bool) -> usize {
     if part2 {
         // Mark non-pipes by walking the pipe loop
-        let size = grid.width * grid.width;
-        let mut no_pipes = bitvec!(1; size);
+        let pipes = &grid.pipes;
+        let mut no_pipes = pipes.to_bits(|_| true);
         let walk_iter = grid.walk();
         let mut last_forward = walk_iter.walk_direction;
         let mut right_turns: isize = 0;
@@ -240,7 +171,7 @@ fn run(input: PuzzleInput, part2: bool) -> usize {
         for (pos, forward) in walk_iter {
             right_turns += if last_forward == forward {
                 0
-            } else if last_forward.right() == forward {
+            } else if last_forward.turn_right() == forward {
                 1
             } else {
                 -1
@@ -252,25 +183,51 @@ fn run(input: PuzzleInput, part2: bool) -> usize {
 
         // Fill inner non-pipe fields
         // -> Expect inner on the right if more right turns than left turns
-        let mut inner_field = bitvec!(0; size);
+        let mut inner_field = BitGrid::new(pipes.width(), pipes.height());
         for (pos, forward) in moves {
             let dir = if right_turns > 0 {
-                forward.right()
+                forward.turn_right()
             } else {
-                forward.left()
+                forward.turn_left()
             };
-            if grid.move_possible(pos, dir) {
-                flood_fill_empty(&mut inner_field, grid.move_pos(pos, dir), &no_pipes, &grid)
+            if let Some(next_pos) = grid.step(pos, dir) {
+                flood_fill_empty(&mut inner_field, next_pos, &no_pipes, grid)
             }
         }
         inner_field.count_ones()
     } else {
-        (grid.walk().count() + 1) / 2
+        grid.walk().count().div_ceil(2)
     }
 }
 
-pub fn solution(input: PuzzleInput, part2: bool) -> String {
-    run(input, part2).to_string()
+/// Steps along the loop to the tile farthest from the start
+pub fn part1(grid: &PipeGrid) -> usize {
+    run(grid, false)
+}
+
+/// Number of tiles enclosed by the loop
+pub fn part2(grid: &PipeGrid) -> usize {
+    run(grid, true)
+}
+
+pub struct Day10;
+
+impl Puzzle for Day10 {
+    type Input = PipeGrid;
+
+    const TITLE: &'static str = "Pipe Maze";
+
+    fn parse(input: PuzzleInput) -> Result<Self::Input, ParseError> {
+        PipeGrid::parse(input)
+    }
+
+    fn part1(grid: &Self::Input, _params: &Params) -> Answer {
+        part1(grid).into()
+    }
+
+    fn part2(grid: &Self::Input, _params: &Params) -> Answer {
+        part2(grid).into()
+    }
 }
 
 #[cfg(test)]
@@ -285,8 +242,9 @@ mod test {
 .L-J.
 ....."
                 .into(),
-        );
-        println!("{:?}", grid.directions);
+        )
+        .unwrap();
+        println!("{}", grid.walk());
         assert!(grid.has_direction(5 + 2, Direction::Right));
         assert!(!grid.has_direction(5 + 2, Direction::Up));
     }
@@ -298,19 +256,31 @@ mod test {
 .|.|.
 .L-J.
 .....";
-        assert_eq!(run(test_input.into(), false), 4);
+        assert_eq!(run(&PipeGrid::parse(test_input.into()).unwrap(), false), 4);
         let test_input2 = r"..F7.
 .FJ|.
 SJ.L7
 |F--J
 LJ...";
-        assert_eq!(run(test_input2.into(), false), 8);
+        assert_eq!(run(&PipeGrid::parse(test_input2.into()).unwrap(), false), 8);
+    }
+
+    #[test]
+    fn test_rectangular() {
+        let test_input = r".......
+.S---7.
+.|...|.
+.L---J.";
+        let grid = PipeGrid::parse(test_input.into()).unwrap();
+        assert_eq!(run(&grid, false), 6);
+        assert_eq!(run(&grid, true), 3);
     }
 
     #[test]
     fn test_part2() {
+        let run_part2 = |input: &'static str| run(&PipeGrid::parse(input.into()).unwrap(), true);
         assert_eq!(
-            run(
+            run_part2(
                 r"...........
 .S-------7.
 .|F-----7|.
@@ -320,13 +290,11 @@ LJ...";
 .|II|O|II|.
 .L--JOL--J.
 .....O....."
-                    .into(),
-                true
             ),
             4
         );
         assert_eq!(
-            run(
+            run_part2(
                 r"..........
 .S------7.
 .|F----7|.
@@ -336,13 +304,11 @@ LJ...";
 .|II||II|.
 .L--JL--J.
 .........."
-                    .into(),
-                true
             ),
             4
         );
         assert_eq!(
-            run(
+            run_part2(
                 r".F----7F7F7F7F-7....
 .|F--7||||||||FJ....
 .||.FJ||||||||L7....
@@ -353,8 +319,6 @@ L--J.L7...LJS7F-7L7.
 .....|FJLJ|FJ|F7|.LJ
 ....FJL-7.||.||||...
 ....L---J.LJ.LJLJ..."
-                    .into(),
-                true
             ),
             8
         );
@@ -1,73 +1,123 @@
+//! Day 11: Cosmic Expansion
+//!
+//! The [`Image`] holds the galaxy positions before the expansion.
+
+use std::num::NonZeroU64;
+
 use itertools::Itertools;
 
-use crate::PuzzleInput;
+use crate::{check_grid_row, Answer, Param, Params, ParseError, Puzzle, PuzzleInput};
 use bitvec::prelude::*;
 
-type Galaxy = (usize, usize);
+/// Expanded galaxy position (wide enough for large expansion factors)
+type Galaxy = (u64, u64);
 
-fn expanded_galaxies(
-    point_rows: Vec<Vec<usize>>,
-    empty_cols: &BitVec,
-    factor: usize,
-) -> Vec<Galaxy> {
+fn expanded_galaxies(point_rows: &[Vec<usize>], empty_cols: &BitVec, factor: u64) -> Vec<Galaxy> {
     point_rows
-        .into_iter()
+        .iter()
         .enumerate()
-        .scan(0_usize, |extra_rows, (row, cols)| {
+        .scan(0_u64, |extra_rows, (row, cols)| {
             if cols.is_empty() {
                 *extra_rows += factor - 1;
             }
-            Some((row + *extra_rows, cols))
+            Some((row as u64 + *extra_rows, cols))
         })
         .flat_map(|(row, cols)| {
-            cols.into_iter().scan(
+            cols.iter().scan(
                 (0, 0),
-                move |(extra_cols, last_col): &mut (usize, usize), col| {
-                    *extra_cols += (factor - 1) * empty_cols[*last_col..col].count_ones();
+                move |(extra_cols, last_col): &mut (u64, usize), &col| {
+                    *extra_cols += (factor - 1) * empty_cols[*last_col..col].count_ones() as u64;
                     *last_col = col;
-                    Some((row, col + *extra_cols))
+                    Some((row, col as u64 + *extra_cols))
                 },
             )
         })
         .collect()
 }
 
-fn distance(point_a: Galaxy, point_b: Galaxy) -> usize {
+fn distance(point_a: Galaxy, point_b: Galaxy) -> u64 {
     point_a.0.abs_diff(point_b.0) + point_a.1.abs_diff(point_b.1)
 }
 
-fn run(mut input: PuzzleInput, expansion_factor: usize) -> usize {
-    let first_line = input.next().unwrap();
-    let mut empty_cols = bitvec!(1; first_line.chars().count());
-
-    let point_rows = [first_line]
-        .into_iter()
-        .chain(input)
-        .map(|line| {
-            line.char_indices()
-                .filter(|(_, c)| *c == '#')
-                .map(|(col, _)| {
-                    empty_cols.set(col, false);
-                    col
-                })
-                .collect_vec()
-        })
-        .collect_vec();
+/// Galaxy columns of each image row and the columns without galaxies
+pub struct Image {
+    point_rows: Vec<Vec<usize>>,
+    empty_cols: BitVec,
+}
 
-    expanded_galaxies(point_rows, &empty_cols, expansion_factor)
+/// Sum of the shortest paths between all pairs of galaxies
+/// with empty rows and columns `expansion_factor` times larger
+pub fn run(image: &Image, expansion_factor: NonZeroU64) -> u64 {
+    expanded_galaxies(&image.point_rows, &image.empty_cols, expansion_factor.get())
         .into_iter()
         .tuple_combinations()
         .map(|(a, b)| distance(a, b))
         .sum()
 }
 
-pub fn solution(input: PuzzleInput, part2: bool) -> String {
-    run(input, if part2 { 1_000_000 } else { 2 }).to_string()
+/// Sum of the shortest paths between all pairs of galaxies with empty rows and columns doubled
+pub fn part1(image: &Image) -> u64 {
+    run(image, Params::defaults(Day11::PARAMS, 1).get("expansion"))
+}
+
+/// Sum of the shortest paths between all pairs of galaxies with empty rows and columns a million times larger
+pub fn part2(image: &Image) -> u64 {
+    run(image, Params::defaults(Day11::PARAMS, 2).get("expansion"))
+}
+
+pub struct Day11;
+
+impl Puzzle for Day11 {
+    type Input = Image;
+
+    const TITLE: &'static str = "Cosmic Expansion";
+
+    const PARAMS: &'static [Param] = &[Param::per_part::<NonZeroU64>(
+        "expansion",
+        "times larger that empty rows and columns become (at least 1)",
+        ["2", "1000000"],
+    )];
+
+    fn parse(mut input: PuzzleInput) -> Result<Self::Input, ParseError> {
+        let first_line = input.next().unwrap_or_default();
+        let width = first_line.chars().count();
+        let mut empty_cols = bitvec!(1; width);
+
+        let point_rows = [first_line]
+            .into_iter()
+            .chain(input)
+            .enumerate()
+            .map(|(row, line)| {
+                check_grid_row(row, &line, width, ".#")?;
+                Ok(line
+                    .char_indices()
+                    .filter(|(_, c)| *c == '#')
+                    .map(|(col, _)| {
+                        empty_cols.set(col, false);
+                        col
+                    })
+                    .collect_vec())
+            })
+            .try_collect()?;
+        Ok(Image {
+            point_rows,
+            empty_cols,
+        })
+    }
+
+    fn part1(image: &Self::Input, params: &Params) -> Answer {
+        run(image, params.get("expansion")).into()
+    }
+
+    fn part2(image: &Self::Input, params: &Params) -> Answer {
+        run(image, params.get("expansion")).into()
+    }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::ParamError;
 
     #[test]
     fn test_run() {
@@ -81,8 +131,21 @@ mod test {
 ..........
 .......#..
 #...#.....";
-        assert_eq!(run(test_input.into(), 2), 374);
-        assert_eq!(run(test_input.into(), 10), 1030);
-        assert_eq!(run(test_input.into(), 100), 8410);
+        let image = Day11::parse(test_input.into()).unwrap();
+        let run = |factor| run(&image, NonZeroU64::new(factor).unwrap());
+        assert_eq!(run(2), 374);
+        assert_eq!(run(10), 1030);
+        assert_eq!(run(100), 8410);
+        // Empty rows and columns stay as they are
+        assert_eq!(run(1), 292);
+
+        let overrides = ["expansion=0".parse().unwrap()];
+        assert_eq!(
+            Params::new(Day11::PARAMS, 1, &overrides),
+            Err(ParamError::Invalid {
+                name: "expansion",
+                value: "0".into()
+            })
+        );
     }
 }
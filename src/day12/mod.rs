@@ -1,6 +1,12 @@
+//! Day 12: Hot Springs
+//!
+//! Each [`SpringRecord`] holds the spring conditions and the sizes of the damaged groups.
+
+use std::num::NonZeroUsize;
+
 use itertools::Itertools;
 
-use crate::PuzzleInput;
+use crate::{parse_token, Answer, Param, Params, ParseError, Puzzle, PuzzleInput};
 
 #[derive(Debug, Clone, Copy)]
 enum Condition {
@@ -31,31 +37,63 @@ fn run_endings(
         .map(move |s| s + run_length)
 }
 
-fn parse(line: &str, repeats: usize) -> (Vec<Option<Condition>>, Vec<usize>) {
-    let (conditions_string, nums_str) = line.splitn(2, ' ').collect_tuple().unwrap();
-    let numbers = nums_str
-        .split(',')
-        .map(|s| s.parse().unwrap())
-        .collect_vec()
-        .repeat(repeats);
-    let conditions = {
-        let mut c = conditions_string
-            .chars()
-            .map(|c| match c {
-                '.' => Some(Condition::Operational),
-                '#' => Some(Condition::Damaged),
-                _ => None,
-            })
+/// Spring conditions (`None` if unknown) and the lengths of damaged runs
+#[derive(Debug, Clone)]
+pub struct SpringRecord {
+    conditions: Vec<Option<Condition>>,
+    run_lengths: Vec<usize>,
+}
+
+impl SpringRecord {
+    fn parse(line: usize, text: &str) -> Result<Self, ParseError> {
+        let (conditions_string, nums_str) = text
+            .split_once(' ')
+            .ok_or_else(|| ParseError::at_end(line, text, "space before damaged runs"))?;
+        Ok(Self {
+            conditions: conditions_string
+                .char_indices()
+                .map(|(col, c)| match c {
+                    '.' => Ok(Some(Condition::Operational)),
+                    '#' => Ok(Some(Condition::Damaged)),
+                    '?' => Ok(None),
+                    _ => Err(ParseError::new(line, col, "one of \".#?\"")),
+                })
+                .try_collect()?,
+            run_lengths: nums_str
+                .split(',')
+                .map(|s| {
+                    parse_token(line, text, s, "run length").and_then(|n| {
+                        if n > 0 {
+                            Ok(n)
+                        } else {
+                            Err(ParseError::at(line, text, s, "positive run length"))
+                        }
+                    })
+                })
+                .try_collect()?,
+        })
+    }
+
+    /// Repeat the record with unknown conditions in between
+    fn unfold(&self, repeats: usize) -> Self {
+        let mut conditions = self
+            .conditions
+            .iter()
+            .copied()
             .chain([None])
             .collect_vec()
             .repeat(repeats);
-        c.pop();
-        c
-    };
-    (conditions, numbers)
+        conditions.pop();
+        Self {
+            conditions,
+            run_lengths: self.run_lengths.repeat(repeats),
+        }
+    }
 }
-fn arrangements(line: &str, part2: bool) -> usize {
-    let (conditions, run_lengths) = parse(line, if part2 { 5 } else { 1 });
+
+fn arrangements(record: &SpringRecord) -> usize {
+    let conditions = &record.conditions;
+    let run_lengths = &record.run_lengths;
 
     // All damaged runs have an 1-sized tail (except the last one)
     let total_run_length = run_lengths.iter().sum::<usize>() + run_lengths.len() - 1;
@@ -75,7 +113,7 @@ fn arrangements(line: &str, part2: bool) -> usize {
     let empty_run_ending_counts = [0_usize].repeat(conditions.len() + 2);
     let mut run_ending_counts = empty_run_ending_counts.clone();
     run_ending_counts[0] = 1;
-    for (run_length, remaining) in run_lengths.into_iter().zip_eq(remaining_run_lengths) {
+    for (&run_length, remaining) in run_lengths.iter().zip_eq(remaining_run_lengths) {
         let mut next_run_ending_counts = empty_run_ending_counts.clone();
         // Count run endings
         for (start, count) in run_ending_counts
@@ -115,12 +153,51 @@ fn arrangements(line: &str, part2: bool) -> usize {
         .sum()
 }
 
-fn run(input: PuzzleInput, part2: bool) -> usize {
-    input.map(|line| arrangements(&line, part2)).sum()
+/// Sum of the possible spring arrangements of all records unfolded to `repeats` copies
+pub fn run(records: &[SpringRecord], repeats: NonZeroUsize) -> usize {
+    records
+        .iter()
+        .map(|record| arrangements(&record.unfold(repeats.get())))
+        .sum()
+}
+
+/// Sum of the possible spring arrangements of all records
+pub fn part1(records: &[SpringRecord]) -> usize {
+    run(records, Params::defaults(Day12::PARAMS, 1).get("repeats"))
 }
 
-pub fn solution(input: PuzzleInput, part2: bool) -> String {
-    run(input, part2).to_string()
+/// Sum of the possible spring arrangements of all records unfolded five times
+pub fn part2(records: &[SpringRecord]) -> usize {
+    run(records, Params::defaults(Day12::PARAMS, 2).get("repeats"))
+}
+
+pub struct Day12;
+
+impl Puzzle for Day12 {
+    type Input = Vec<SpringRecord>;
+
+    const TITLE: &'static str = "Hot Springs";
+
+    const PARAMS: &'static [Param] = &[Param::per_part::<NonZeroUsize>(
+        "repeats",
+        "copies of each record after unfolding (at least 1)",
+        ["1", "5"],
+    )];
+
+    fn parse(input: PuzzleInput) -> Result<Self::Input, ParseError> {
+        input
+            .enumerate()
+            .map(|(line, text)| SpringRecord::parse(line, &text))
+            .collect()
+    }
+
+    fn part1(records: &Self::Input, params: &Params) -> Answer {
+        run(records, params.get("repeats")).into()
+    }
+
+    fn part2(records: &Self::Input, params: &Params) -> Answer {
+        run(records, params.get("repeats")).into()
+    }
 }
 
 #[cfg(test)]
@@ -136,22 +213,24 @@ mod test {
 ????.######..#####. 1,6,5
 ?###???????? 3,2,1";
 
-        let lines = test_input.lines().collect_vec();
-
-        assert_eq!(arrangements(lines[0], false), 1);
-        assert_eq!(arrangements(lines[1], false), 4);
-        assert_eq!(arrangements(lines[2], false), 1);
-        assert_eq!(arrangements(lines[3], false), 1);
-        assert_eq!(arrangements(lines[4], false), 4);
-        assert_eq!(arrangements(lines[5], false), 10);
-        assert_eq!(run(test_input.into(), false), 21);
-
-        assert_eq!(arrangements(lines[0], true), 1);
-        assert_eq!(arrangements(lines[1], true), 16384);
-        assert_eq!(arrangements(lines[2], true), 1);
-        assert_eq!(arrangements(lines[3], true), 16);
-        assert_eq!(arrangements(lines[4], true), 2500);
-        assert_eq!(arrangements(lines[5], true), 506250);
-        assert_eq!(run(test_input.into(), true), 525152);
+        let records = Day12::parse(test_input.into()).unwrap();
+        let arrangements_unfolded =
+            |index: usize, repeats| arrangements(&records[index].unfold(repeats));
+
+        assert_eq!(arrangements_unfolded(0, 1), 1);
+        assert_eq!(arrangements_unfolded(1, 1), 4);
+        assert_eq!(arrangements_unfolded(2, 1), 1);
+        assert_eq!(arrangements_unfolded(3, 1), 1);
+        assert_eq!(arrangements_unfolded(4, 1), 4);
+        assert_eq!(arrangements_unfolded(5, 1), 10);
+        assert_eq!(run(&records, NonZeroUsize::MIN), 21);
+
+        assert_eq!(arrangements_unfolded(0, 5), 1);
+        assert_eq!(arrangements_unfolded(1, 5), 16384);
+        assert_eq!(arrangements_unfolded(2, 5), 1);
+        assert_eq!(arrangements_unfolded(3, 5), 16);
+        assert_eq!(arrangements_unfolded(4, 5), 2500);
+        assert_eq!(arrangements_unfolded(5, 5), 506250);
+        assert_eq!(run(&records, NonZeroUsize::new(5).unwrap()), 525152);
     }
 }
@@ -1,60 +1,45 @@
+//! Day 13: Point of Incidence
+//!
+//! Each [`GridPattern`] holds the rocks of a pattern by rows and columns.
+
 use itertools::Itertools;
 
-use crate::{split_by_empty_line, PuzzleInput};
+use crate::{Answer, BitGrid, GridShape, Params, ParseError, Puzzle, PuzzleInput};
 
 use bitvec::prelude::*;
 
 #[derive(Debug, Clone)]
-struct GridPattern {
-    rocks: BitVec,
-    width: usize,
-    height: usize,
+pub struct GridPattern {
+    rocks: BitGrid,
 }
 
 impl GridPattern {
-    fn parse(mut lines: impl Iterator<Item = String>) -> Self {
-        let first_line = lines.next().unwrap();
-        let width = first_line.chars().count();
-        let rocks: BitVec = [first_line]
-            .into_iter()
-            .chain(lines)
-            .filter(|line| !line.is_empty())
-            .map(|line| {
-                let mut v = bitvec![0; width];
-                for p in line.chars().positions(|c| c == '#') {
-                    v.set(p, true);
-                }
-                v
-            })
-            .concat();
-        Self {
-            width,
-            height: rocks.len() / width,
-            rocks,
-        }
-    }
-    fn row(&self, row: usize) -> &BitSlice {
-        &self.rocks[row * self.width..(row + 1) * self.width]
+    fn parse(lines: impl Iterator<Item = (usize, String)>) -> Result<Self, ParseError> {
+        Ok(Self {
+            rocks: BitGrid::parse(lines, ".#", '#')?,
+        })
     }
 
     /// Find row indices above reflection centers
     fn reflected_rows(&self) -> impl Iterator<Item = usize> + '_ {
-        (0..self.height - 1).filter(|&row| {
+        let height = self.rocks.height();
+        (0..height - 1).filter(move |&row| {
             // Confirm complete row reflection
-            let radius = row.min(self.height - 2 - row);
-            (0..=radius).all(|r| self.row(row - r) == self.row(row + 1 + r))
+            let radius = row.min(height - 2 - row);
+            (0..=radius).all(|r| self.rocks.row(row - r) == self.rocks.row(row + 1 + r))
         })
     }
 
     /// Find column indices to the left of reflection centers
     fn reflected_cols(&self) -> Vec<usize> {
         // Check row by row (for better cpu-cache utilization)
-        let mut reflection_candidates = bitvec!(1; self.width-1);
-        for h in 0..self.height {
-            let row = self.row(h);
-            for col in 0..self.width - 1 {
+        let width = self.rocks.width();
+        let mut reflection_candidates = bitvec!(1; width - 1);
+        for h in 0..self.rocks.height() {
+            let row = self.rocks.row(h);
+            for col in 0..width - 1 {
                 if reflection_candidates[col]
-                    && (0..=/*scan radius of reflection*/col.min(self.width - 2 - col))
+                    && (0..=/*scan radius of reflection*/col.min(width - 2 - col))
                         .any(|r| row[col - r] != row[col + 1 + r])
                 {
                     // Eliminate single col reflection candidate
@@ -73,15 +58,16 @@ fn summaries(g: &GridPattern) -> impl Iterator<Item = usize> + '_ {
         .chain(g.reflected_rows().map(|row| (row + 1) * 100))
 }
 
-fn run(input: PuzzleInput, part2: bool) -> usize {
-    split_by_empty_line!(input)
-        .map(|lines| GridPattern::parse(lines))
+fn run(patterns: &[GridPattern], part2: bool) -> usize {
+    patterns
+        .iter()
+        .cloned()
         .map(|mut gp| {
             let sum = summaries(&gp).next().expect("either row or col reflection");
             if part2 {
                 (0..gp.rocks.len())
                     .find_map(|index| {
-                        let val = gp.rocks[index];
+                        let val = gp.rocks.get(index);
                         gp.rocks.set(index, !val);
                         let s = summaries(&gp).find(|&s| s != sum);
                         gp.rocks.set(index, val);
@@ -95,8 +81,40 @@ fn run(input: PuzzleInput, part2: bool) -> usize {
         .sum()
 }
 
-pub fn solution(input: PuzzleInput, part2: bool) -> String {
-    run(input, part2).to_string()
+/// Summary of the reflection lines of all patterns
+pub fn part1(patterns: &[GridPattern]) -> usize {
+    run(patterns, false)
+}
+
+/// Summary of the reflection lines of all patterns after fixing a single smudge
+pub fn part2(patterns: &[GridPattern]) -> usize {
+    run(patterns, true)
+}
+
+pub struct Day13;
+
+impl Puzzle for Day13 {
+    type Input = Vec<GridPattern>;
+
+    const TITLE: &'static str = "Point of Incidence";
+
+    fn parse(input: PuzzleInput) -> Result<Self::Input, ParseError> {
+        input
+            .enumerate()
+            .group_by(|(_, line)| line.is_empty())
+            .into_iter()
+            .filter(|(empty, _)| !empty)
+            .map(|(_, lines)| GridPattern::parse(lines))
+            .collect()
+    }
+
+    fn part1(patterns: &Self::Input, _params: &Params) -> Answer {
+        part1(patterns).into()
+    }
+
+    fn part2(patterns: &Self::Input, _params: &Params) -> Answer {
+        part2(patterns).into()
+    }
 }
 
 #[cfg(test)]
@@ -120,7 +138,8 @@ mod test {
 #####.##.
 ..##..###
 #....#..#";
-        assert_eq!(run(test_input.into(), false), 405);
-        assert_eq!(run(test_input.into(), true), 400);
+        let patterns = Day13::parse(test_input.into()).unwrap();
+        assert_eq!(run(&patterns, false), 405);
+        assert_eq!(run(&patterns, true), 400);
     }
 }
@@ -1,90 +1,51 @@
-use std::collections::hash_map::Entry;
-use std::{collections::HashMap, fmt::Display};
-
-use bitvec::prelude::*;
-
-use crate::PuzzleInput;
-
-enum Orientation {
-    North,
-    West,
-    South,
-    East,
-}
-
-impl Orientation {
-    fn couter_clockwise() -> [Orientation; 4] {
-        [
-            Orientation::North,
-            Orientation::West,
-            Orientation::South,
-            Orientation::East,
-        ]
-    }
-
-    /// Transform a position from this orientation to north and return its index
-    fn index(&self, row: usize, col: usize, size: usize) -> usize {
-        let (r, c) = match self {
-            Orientation::North => (row, col),
-            Orientation::West => (size - 1 - col, row),
-            Orientation::South => (size - 1 - row, size - 1 - col),
-            Orientation::East => (col, size - 1 - row),
-        };
-        r * size + c
-    }
-}
-
-#[derive(Debug, PartialEq, Eq, Hash)]
-struct RockGrid {
-    round_rocks: BitVec,
-    cube_rocks: Vec<bool>,
-    size: usize,
+//! Day 14: Parabolic Reflector Dish
+//!
+//! The [`RockGrid`] holds the rounded and cube-shaped rocks of the platform.
+
+use std::fmt::Display;
+
+use crate::{
+    fast_forward, Answer, BitGrid, Grid, GridShape, Param, Params, ParseError, Puzzle, PuzzleInput,
+};
+
+/// Grid of round rocks rolling between fixed cube rocks
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RockGrid {
+    round_rocks: BitGrid,
+    /// Cube rocks rotated so that north, west, south and east are up.
+    /// (Column access is faster for `Grid<bool>` than for `BitGrid`.)
+    cube_rocks: [Grid<bool>; 4],
 }
 
 impl RockGrid {
-    fn parse(mut input: impl Iterator<Item = String>) -> Self {
-        let first_line = input.next().unwrap();
-        let size = first_line.chars().count();
-        let mut round_rocks = bitvec!(0; size * size);
-        let mut cube_rocks = round_rocks.clone();
-        for (row, line) in [first_line].into_iter().chain(input).enumerate() {
-            for (col, c) in line.char_indices() {
-                let index = row * size + col;
-                match c {
-                    '#' => cube_rocks.set(index, true),
-                    'O' => round_rocks.set(index, true),
-                    _ => (),
-                }
-            }
-        }
-        Self {
-            round_rocks,
-            // Column access is faster for Vec<bool> than for BitVec.
-            // (Precomputing BitVec rotations is about as fast as Vec<bool>.)
-            cube_rocks: cube_rocks.into_iter().collect(),
-            size,
-        }
+    fn parse(input: impl Iterator<Item = String>) -> Result<Self, ParseError> {
+        let grid = Grid::parse(input.enumerate(), ".#O", |c| c)?;
+        let north = grid.map(|&c| c == '#');
+        let west = north.rotated_clockwise();
+        let south = west.rotated_clockwise();
+        let east = north.rotated_counter_clockwise();
+        Ok(Self {
+            round_rocks: grid.to_bits(|&c| c == 'O'),
+            cube_rocks: [north, west, south, east],
+        })
     }
 
-    fn row<'a>(&self, v: &'a BitVec, row: usize) -> &'a BitSlice {
-        &v[row * self.size..(row + 1) * self.size]
-    }
-
-    fn roll_up_and_west_to_up(&self, cube_rock_orientation: Orientation) -> BitVec {
-        let mut block_height = [0].repeat(self.size);
-        let mut rolled_round_rocks = bitvec!(0; self.round_rocks.len());
-        for row in 0..self.size {
-            for col in (0..self.size)
-                .filter(|&col| self.cube_rocks[cube_rock_orientation.index(row, col, self.size)])
-            {
+    /// Roll `round_rocks` up towards the up-facing cube rocks `cube_rocks`
+    /// and rotate the result to the right
+    fn roll_up_and_west_to_up(round_rocks: &BitGrid, cube_rocks: &Grid<bool>) -> BitGrid {
+        let (width, height) = (round_rocks.width(), round_rocks.height());
+        let mut block_height = [0].repeat(width);
+        let mut rolled_round_rocks = BitGrid::new(height, width);
+        for row in 0..height {
+            for col in (0..width).filter(|&col| cube_rocks[(row, col)]) {
                 // Column has a blocking cube rock
                 block_height[col] = row + 1;
             }
-            for col in self.row(&self.round_rocks, row).iter_ones() {
+            for col in round_rocks.row(row).iter_ones() {
                 // Column has falling round rock
                 rolled_round_rocks.set(
-                    // Rotate left by transforming index East to North
-                    Orientation::East.index(block_height[col], col, self.size),
+                    // Rotate right so that west is up
+                    rolled_round_rocks.position(col, height - 1 - block_height[col]),
                     true,
                 );
                 block_height[col] += 1;
@@ -93,34 +54,37 @@ impl RockGrid {
         rolled_round_rocks
     }
 
-    fn cycle(&mut self) {
-        for cube_rock_orientation in Orientation::couter_clockwise() {
-            // Rotate target direction to up/north
-            self.round_rocks = self.roll_up_and_west_to_up(cube_rock_orientation);
-        }
+    /// Round rocks after rolling `round_rocks` north, west, south and east
+    fn cycled(&self, round_rocks: &BitGrid) -> BitGrid {
+        self.cube_rocks
+            .iter()
+            .fold(round_rocks.clone(), |rocks, cube_rocks| {
+                // Rotate target direction to up/north
+                Self::roll_up_and_west_to_up(&rocks, cube_rocks)
+            })
     }
 
     fn north_load(&self) -> usize {
-        (0..self.size)
-            .map(|row| self.row(&self.round_rocks, row).count_ones() * (self.size - row))
+        let height = self.round_rocks.height();
+        (0..height)
+            .map(|row| self.round_rocks.row(row).count_ones() * (height - row))
             .sum()
     }
 
     /// Part 1 compute north load after rolling up without mutating grid
     fn north_load_after_roll_up(&self) -> usize {
-        let mut load_by_column = [0].repeat(self.size);
+        let (width, height) = (self.round_rocks.width(), self.round_rocks.height());
+        let mut load_by_column = [0].repeat(width);
         let mut block_height = load_by_column.clone();
 
-        for row in 0..self.size {
-            for col in (0..self.size)
-                .filter(|&col| self.cube_rocks[Orientation::North.index(row, col, self.size)])
-            {
+        for row in 0..height {
+            for col in (0..width).filter(|&col| self.cube_rocks[0][(row, col)]) {
                 // Column has a blocking cube rock
                 block_height[col] = row + 1;
             }
-            for col in self.row(&self.round_rocks, row).iter_ones() {
+            for col in self.round_rocks.row(row).iter_ones() {
                 // Column has falling round rock
-                load_by_column[col] += self.size - block_height[col];
+                load_by_column[col] += height - block_height[col];
                 block_height[col] += 1;
             }
         }
@@ -130,80 +94,60 @@ impl RockGrid {
 
 impl Display for RockGrid {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        for r in 0..self.size {
-            let rrow = self.row(&self.round_rocks, r);
-            writeln!(
-                f,
-                "{}",
-                (0..self.size)
-                    .map(
-                        |col| if self.cube_rocks[Orientation::North.index(r, col, self.size)] {
-                            '#'
-                        } else if rrow[col] {
-                            'O'
-                        } else {
-                            '.'
-                        }
-                    )
-                    .collect::<String>()
-            )?;
+        let mut cells = self.cube_rocks[0].map(|&cube| if cube { '#' } else { '.' });
+        for p in self.round_rocks.iter_ones() {
+            cells[p] = 'O';
         }
-        Ok(())
+        write!(f, "{cells}")
     }
 }
 
-struct History(HashMap<usize, Vec<(usize, BitVec)>>);
+/// Total load on the north support beams after `cycles` spin cycles
+pub fn north_load_after_cycles(grid: &RockGrid, cycles: usize) -> usize {
+    // The rocks settle into a repeating pattern after some cycles
+    let mut grid = grid.clone();
+    grid.round_rocks = fast_forward(
+        grid.round_rocks.clone(),
+        |round_rocks| grid.cycled(round_rocks),
+        cycles,
+    );
+    grid.north_load()
+}
+
+/// Total load on the north support beams after tilting north
+pub fn part1(grid: &RockGrid) -> usize {
+    grid.north_load_after_roll_up()
+}
 
-impl History {
-    fn new() -> Self {
-        Self(HashMap::new())
-    }
+/// Total load on the north support beams after a billion spin cycles
+pub fn part2(grid: &RockGrid) -> usize {
+    north_load_after_cycles(grid, Params::defaults(Day14::PARAMS, 2).get("cycles"))
+}
 
-    // Insert a (load => (cycle, rocks)) entry.
-    // If rocks has already been inserted, return its cycle instead.
-    fn insert(&mut self, load: usize, cycle: usize, rocks: &BitVec) -> Option<usize> {
-        let history = match self.0.entry(load) {
-            Entry::Vacant(entry) => entry.insert(vec![]),
-            Entry::Occupied(entry) => entry.into_mut(),
-        };
-        if let Some(cycle) = history
-            .iter()
-            .find(|(_, history_rocks)| history_rocks == rocks)
-            .map(|(cycle, _)| *cycle)
-        {
-            Some(cycle)
-        } else {
-            history.push((cycle, rocks.clone()));
-            None
-        }
+pub struct Day14;
+
+impl Puzzle for Day14 {
+    type Input = RockGrid;
+
+    const TITLE: &'static str = "Parabolic Reflector Dish";
+
+    const PARAMS: &'static [Param] = &[Param::new::<usize>(
+        "cycles",
+        "spin cycles of part 2",
+        "1000000000",
+    )];
+
+    fn parse(input: PuzzleInput) -> Result<Self::Input, ParseError> {
+        RockGrid::parse(input)
     }
-}
 
-fn run(input: PuzzleInput, part2: bool) -> usize {
-    let mut grid = RockGrid::parse(input);
-    if part2 {
-        let mut cycle = 0;
-        let mut cycle_history = History::new();
-        let cycle_target = 1000000000;
-        while cycle < cycle_target {
-            grid.cycle();
-            cycle += 1;
-            let load = grid.north_load();
-            if let Some(prev_cycle) = cycle_history.insert(load, cycle, &grid.round_rocks) {
-                // Use a repeating cycle to skip cycles until near target
-                let cycle_len = cycle - prev_cycle;
-                let remaining = cycle_target - cycle;
-                cycle += cycle_len * (remaining / cycle_len);
-            }
-        }
-        grid.north_load()
-    } else {
-        grid.north_load_after_roll_up()
+    fn part1(grid: &Self::Input, _params: &Params) -> Answer {
+        part1(grid).into()
     }
-}
 
-pub fn solution(input: PuzzleInput, part2: bool) -> String {
-    run(input, part2).to_string()
+    fn part2(grid: &Self::Input, params: &Params) -> Answer {
+        north_load_after_cycles(grid, params.get("cycles")).into()
+    }
 }
 
 #[cfg(test)]
@@ -222,9 +166,10 @@ O.#..O.#.#
 .......O..
 #....###..
 #OO..#....";
-        assert_eq!(run(test_input.into(), false), 136);
-        let mut grid = RockGrid::parse(PuzzleInput::from(test_input));
-        grid.cycle();
+        let initial_grid = RockGrid::parse(PuzzleInput::from(test_input)).unwrap();
+        assert_eq!(part1(&initial_grid), 136);
+        let mut grid = initial_grid.clone();
+        grid.round_rocks = grid.cycled(&grid.round_rocks);
         assert_eq!(
             ".....#....
 ....#...O#
@@ -238,7 +183,7 @@ O.#..O.#.#
 #..OO#....",
             grid.to_string().trim_end()
         );
-        grid.cycle();
+        grid.round_rocks = grid.cycled(&grid.round_rocks);
         assert_eq!(
             ".....#....
 ....#...O#
@@ -252,7 +197,7 @@ O.#..O.#.#
 #.OOO#...O",
             grid.to_string().trim_end()
         );
-        grid.cycle();
+        grid.round_rocks = grid.cycled(&grid.round_rocks);
         assert_eq!(
             ".....#....
 ....#...O#
@@ -267,6 +212,25 @@ O.#..O.#.#
             grid.to_string().trim_end()
         );
         assert_eq!(grid.north_load(), 69);
-        assert_eq!(run(test_input.into(), true), 64);
+        assert_eq!(north_load_after_cycles(&initial_grid, 3), 69);
+        assert_eq!(part2(&initial_grid), 64);
+    }
+
+    #[test]
+    fn test_rectangular() {
+        // Cube rocks along the east edge block like the edge itself
+        let test_input = r"O....#....##
+O.OO#....###
+.....##...##
+OO.#O....O##
+.O.....O#.##
+O.#..O.#.###
+..O..#O..O##
+.......O..##
+#....###..##
+#OO..#....##";
+        let grid = RockGrid::parse(PuzzleInput::from(test_input)).unwrap();
+        assert_eq!(part1(&grid), 136);
+        assert_eq!(north_load_after_cycles(&grid, 1_000_000_000), 64);
     }
 }
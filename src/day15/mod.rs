@@ -1,4 +1,8 @@
-use crate::PuzzleInput;
+//! Day 15: Lens Library
+//!
+//! The initialization sequence is kept as a single line.
+
+use crate::{Answer, Params, ParseError, Puzzle, PuzzleInput};
 
 #[derive(Debug)]
 enum Operation {
@@ -64,8 +68,52 @@ fn run(line: &str, part2: bool) -> usize {
     }
 }
 
-pub fn solution(mut input: PuzzleInput, part2: bool) -> String {
-    run(&input.next().unwrap(), part2).to_string()
+/// Sum of the HASH values of all initialization steps
+pub fn part1(line: &str) -> usize {
+    run(line, false)
+}
+
+/// Focusing power of all lenses after the initialization sequence
+pub fn part2(line: &str) -> usize {
+    run(line, true)
+}
+
+pub struct Day15;
+
+impl Puzzle for Day15 {
+    type Input = String;
+
+    const TITLE: &'static str = "Lens Library";
+
+    fn parse(mut input: PuzzleInput) -> Result<Self::Input, ParseError> {
+        let line = input
+            .next()
+            .ok_or_else(|| ParseError::new(0, 0, "initialization sequence"))?;
+        // Each step is either `<label>-` or `<label>=<focal length>`
+        if let Some(step) = line.split(',').find(|step| {
+            let (label, op) = step.split_at(step.len().saturating_sub(1));
+            let label_valid = |label: &str| !label.is_empty() && !label.contains(['=', '-']);
+            !(op == "-" && label_valid(label)
+                || op.chars().all(|c| c.is_ascii_digit())
+                    && label.strip_suffix('=').is_some_and(label_valid))
+        }) {
+            return Err(ParseError::at(
+                0,
+                &line,
+                step,
+                "'<label>-' or '<label>=<digit>'",
+            ));
+        }
+        Ok(line)
+    }
+
+    fn part1(line: &Self::Input, _params: &Params) -> Answer {
+        part1(line).into()
+    }
+
+    fn part2(line: &Self::Input, _params: &Params) -> Answer {
+        part2(line).into()
+    }
 }
 
 #[cfg(test)]
@@ -78,4 +126,12 @@ mod test {
         assert_eq!(run(test_input, false), 1320);
         assert_eq!(run(test_input, true), 145);
     }
+
+    #[test]
+    fn test_parse_error() {
+        assert_eq!(
+            Day15::parse("rn=1,cm-,qp3".into()),
+            Err(ParseError::new(0, 9, "'<label>-' or '<label>=<digit>'"))
+        );
+    }
 }
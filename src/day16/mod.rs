@@ -1,42 +1,25 @@
+//! Day 16: The Floor Will Be Lava
+//!
+//! The [`ContraptionNetwork`] links each mirror and splitter to the ones a beam reaches next.
+
 use std::collections::{HashSet, VecDeque};
 
-use bitvec::prelude::*;
 use either::Either;
 use itertools::Itertools;
 
-use crate::PuzzleInput;
-
-/// 0 => Right, 1 => Up, 2 => Left, 3 => Down
-type Direction = usize;
-const RIGHT: Direction = 0;
-const UP: Direction = 1;
-const LEFT: Direction = 2;
-const DOWN: Direction = 3;
-
-type Position = usize;
+use crate::{
+    Answer, BitGrid, Direction, Grid, GridShape, Params, ParseError, Position, Puzzle, PuzzleInput,
+};
 
 fn rotate(d: Direction, contraption: Contraption) -> Direction {
-    let vertical = d % 2 == 1;
-    (d + (match contraption {
-        Contraption::CounterClockwise => {
-            if vertical {
-                3
-            } else {
-                1
-            }
-        }
-        Contraption::Clockwise => {
-            if vertical {
-                1
-            } else {
-                3
-            }
-        }
-        _ => 0,
-    })) % 4
+    match contraption {
+        Contraption::CounterClockwise => d.reflect_slash(),
+        Contraption::Clockwise => d.reflect_backslash(),
+        _ => d,
+    }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy)]
 enum Contraption {
     CounterClockwise,
     Clockwise,
@@ -73,77 +56,74 @@ enum Step {
 }
 
 /// Network of beam mirrors and splitters
-struct ContraptionNetwork {
-    nodes: Vec<Option<Node>>,
-    size: usize,
-}
-fn position(row: usize, col: usize, size: usize) -> Position {
-    size * row + col
+pub struct ContraptionNetwork {
+    nodes: Grid<Option<Node>>,
 }
 
 impl ContraptionNetwork {
-    fn parse(mut input: impl Iterator<Item = String>) -> Self {
-        let first_line = input.next().unwrap();
-        let size = first_line.chars().count();
-        let mut from_up: Vec<Option<Position>> = (0..size).map(|_| None).collect();
-        let mut nodes: Vec<Option<Node>> = (0..size * size).map(|_| None).collect();
-        for (row, line) in [first_line].into_iter().chain(input).enumerate() {
+    fn parse(input: impl Iterator<Item = String>) -> Result<Self, ParseError> {
+        let contraptions = Grid::parse(input.enumerate(), "./\\-|", Contraption::parse)?;
+        if contraptions.is_empty() {
+            return Err(ParseError::new(0, 0, "grid row"));
+        }
+        let mut nodes = contraptions.map(|_| None);
+        let mut from_up: Vec<Option<Position>> = vec![None; contraptions.width()];
+        for row in 0..contraptions.height() {
             let mut from_left: Option<Position> = None;
-            for (col, c) in line.char_indices() {
-                if let Some(contraption) = Contraption::parse(c) {
-                    let node = Node {
+            for (col, from_up) in from_up.iter_mut().enumerate() {
+                let pos = nodes.position(row, col);
+                if let Some(contraption) = contraptions[pos] {
+                    nodes[pos] = Some(Node {
                         contraption,
-                        neighbors: [None, from_up[col], from_left, None],
-                    };
-                    let pos = position(row, col, size);
-                    nodes[pos] = Some(node);
-                    if let Some(Some(left)) = from_left.and_then(|u| nodes.get_mut(u)) {
-                        left.neighbors[RIGHT] = Some(pos)
+                        neighbors: [None, *from_up, from_left, None],
+                    });
+                    if let Some(Some(left)) = from_left.map(|l| &mut nodes[l]) {
+                        left.neighbors[Direction::Right.index()] = Some(pos)
                     }
 
-                    if let Some(Some(up)) = from_up[col].and_then(|u| nodes.get_mut(u)) {
-                        up.neighbors[DOWN] = Some(pos)
+                    if let Some(Some(up)) = from_up.map(|u| &mut nodes[u]) {
+                        up.neighbors[Direction::Down.index()] = Some(pos)
                     }
 
-                    from_up[col] = Some(pos);
+                    *from_up = Some(pos);
                     from_left = Some(pos);
                 }
             }
         }
-        Self { nodes, size }
+        Ok(Self { nodes })
+    }
+
+    fn width(&self) -> usize {
+        self.nodes.width()
+    }
+
+    fn height(&self) -> usize {
+        self.nodes.height()
     }
 
     fn boundary(&self, node_position: Position, direction: Direction) -> Position {
-        position(
+        let (row, col) = self.nodes.row_col(node_position);
+        self.nodes.position(
             match direction {
-                UP => 0,
-                DOWN => self.size - 1,
-                _ =>
-                /*keep row*/
-                {
-                    node_position / self.size
-                }
+                Direction::Up => 0,
+                Direction::Down => self.height() - 1,
+                _ => row,
             },
             match direction {
-                RIGHT => self.size - 1,
-                LEFT => 0,
-                _ =>
-                /*keep col*/
-                {
-                    node_position % self.size
-                }
+                Direction::Right => self.width() - 1,
+                Direction::Left => 0,
+                _ => col,
             },
-            self.size,
         )
     }
 
     fn step(&self, beam: Beam) -> Step {
         let (node_position, direction) = beam;
-        if let Some(Some(n)) = self.nodes.get(node_position) {
+        if let Some(Some(n)) = self.nodes.cells().get(node_position) {
             let forward = |contraption| {
                 let out_direction = rotate(direction, contraption);
                 (
-                    n.neighbors[out_direction]
+                    n.neighbors[out_direction.index()]
                         .unwrap_or_else(|| self.boundary(node_position, out_direction)),
                     out_direction,
                 )
@@ -153,10 +133,10 @@ impl ContraptionNetwork {
                     Step::Forward(forward(Contraption::CounterClockwise))
                 }
                 Contraption::Clockwise => Step::Forward(forward(Contraption::Clockwise)),
-                Contraption::Horizontal if direction % 2 == 0 => {
+                Contraption::Horizontal if direction.is_horizontal() => {
                     Step::Forward(forward(Contraption::Horizontal))
                 }
-                Contraption::Vertical if direction % 2 == 1 => {
+                Contraption::Vertical if direction.is_vertical() => {
                     Step::Forward(forward(Contraption::Vertical))
                 }
                 _ => Step::Split(
@@ -177,30 +157,26 @@ impl ContraptionNetwork {
     fn line(&self, origin_beam: Beam, target_beam: Beam) -> impl Iterator<Item = Position> {
         let (origin, _) = origin_beam;
         let (target, input_direction) = target_beam;
+        let width = self.width();
         debug_assert!({
-            let vertical = input_direction % 2 == 1;
-            if vertical {
-                (target % self.size) == (origin % self.size)
+            if input_direction.is_vertical() {
+                (target % width) == (origin % width)
             } else {
-                (target / self.size) == (origin / self.size)
+                (target / width) == (origin / width)
             }
         });
         match input_direction {
-            d @ (RIGHT | LEFT) => Either::Left(match d {
-                RIGHT => Either::Left(origin..=target),
-                _ => Either::Right((target..=origin).rev()),
-            }),
-            d => Either::Right(match d {
-                UP => Either::Left((target..=origin).rev().step_by(self.size)),
-                _ => Either::Right((origin..=target).step_by(self.size)),
-            }),
+            Direction::Right => Either::Left(Either::Left(origin..=target)),
+            Direction::Left => Either::Left(Either::Right((target..=origin).rev())),
+            Direction::Up => Either::Right(Either::Left((target..=origin).rev().step_by(width))),
+            Direction::Down => Either::Right(Either::Right((origin..=target).step_by(width))),
         }
         .into_iter()
     }
 }
 
 fn energize_with_beam(grid: &ContraptionNetwork, start_beam: Beam) -> usize {
-    let mut energized = bitvec!(0; grid.size * grid.size);
+    let mut energized = BitGrid::new(grid.width(), grid.height());
     let mut beams: VecDeque<Beam> = [start_beam].into_iter().collect();
     let mut visited_beams: HashSet<Beam> = HashSet::new();
     let mut energize = |beam, next_beam| {
@@ -232,65 +208,61 @@ fn energize_with_beam(grid: &ContraptionNetwork, start_beam: Beam) -> usize {
             }
         }
     }
-    /*
-    for row in 0..grid.size {
-        println!(
-            "{}",
-            grid.nodes[row * grid.size..(row + 1) * grid.size]
-                .iter()
-                .map(|n| if let Some(node) = n {
-                    match node.contraption {
-                        Contraption::CounterClockwise => '/',
-                        Contraption::Clockwise => '\\',
-                        Contraption::Horizontal => '-',
-                        Contraption::Vertical => '|',
-                    }
-                } else {
-                    '.'
-                })
-                .collect::<String>()
-        );
-    }
-    for row in 0..grid.size {
-        println!(
-            "{}",
-            energized[row * grid.size..(row + 1) * grid.size]
-                .into_iter()
-                .map(|b| if *b { '#' } else { '.' })
-                .collect::<String>()
-        );
-    }
-    */
+    // (Energized tiles may be printed with `println!("{energized}")`)
     energized.count_ones()
 }
 
-fn run(input: PuzzleInput, part2: bool) -> usize {
-    let grid = ContraptionNetwork::parse(input);
+fn run(grid: &ContraptionNetwork, part2: bool) -> usize {
     if part2 {
-        let e = grid.size - 1;
+        let (height, width) = (grid.height(), grid.width());
+        let (last_row, last_col) = (height - 1, width - 1);
         [
-            (0..=0, 0..=e, DOWN),
-            (e..=e, 0..=e, UP),
-            (1..=e - 1, 0..=0, RIGHT),
-            (1..=e - 1, e..=e, LEFT),
+            (0..1, 0..width, Direction::Down),
+            (last_row..height, 0..width, Direction::Up),
+            (1..last_row, 0..1, Direction::Right),
+            (1..last_row, last_col..width, Direction::Left),
         ]
         .into_iter()
         .flat_map(|(rows, cols, dir)| {
             rows.cartesian_product(cols)
-                .map(move |(r, c)| (position(r, c, grid.size), dir))
+                .map(move |(r, c)| (grid.nodes.position(r, c), dir))
         })
-        .inspect(|v| println!("{v:?}"))
-        .map(|(p, dir)| energize_with_beam(&grid, (p, dir)))
-        .inspect(|value| println!("{value}"))
+        .map(|(p, dir)| energize_with_beam(grid, (p, dir)))
         .max()
         .unwrap()
     } else {
-        energize_with_beam(&grid, (0, RIGHT))
+        energize_with_beam(grid, (0, Direction::Right))
     }
 }
 
-pub fn solution(input: PuzzleInput, part2: bool) -> String {
-    run(input, part2).to_string()
+/// Number of tiles energized by a beam entering the top left tile heading right
+pub fn part1(grid: &ContraptionNetwork) -> usize {
+    run(grid, false)
+}
+
+/// Most tiles energized by any beam entering from an edge
+pub fn part2(grid: &ContraptionNetwork) -> usize {
+    run(grid, true)
+}
+
+pub struct Day16;
+
+impl Puzzle for Day16 {
+    type Input = ContraptionNetwork;
+
+    const TITLE: &'static str = "The Floor Will Be Lava";
+
+    fn parse(input: PuzzleInput) -> Result<Self::Input, ParseError> {
+        ContraptionNetwork::parse(input)
+    }
+
+    fn part1(grid: &Self::Input, _params: &Params) -> Answer {
+        part1(grid).into()
+    }
+
+    fn part2(grid: &Self::Input, _params: &Params) -> Answer {
+        part2(grid).into()
+    }
 }
 
 #[cfg(test)]
@@ -299,22 +271,55 @@ mod test {
     #[test]
     fn test_rotate() {
         // "-" / "|"
-        assert_eq!(rotate(RIGHT, Contraption::Horizontal), RIGHT);
-        assert_eq!(rotate(UP, Contraption::Vertical), UP);
-        assert_eq!(rotate(LEFT, Contraption::Horizontal), LEFT);
-        assert_eq!(rotate(DOWN, Contraption::Vertical), DOWN);
+        assert_eq!(
+            rotate(Direction::Right, Contraption::Horizontal),
+            Direction::Right
+        );
+        assert_eq!(rotate(Direction::Up, Contraption::Vertical), Direction::Up);
+        assert_eq!(
+            rotate(Direction::Left, Contraption::Horizontal),
+            Direction::Left
+        );
+        assert_eq!(
+            rotate(Direction::Down, Contraption::Vertical),
+            Direction::Down
+        );
 
         // "/"
-        assert_eq!(rotate(RIGHT, Contraption::CounterClockwise), UP);
-        assert_eq!(rotate(UP, Contraption::CounterClockwise), RIGHT);
-        assert_eq!(rotate(LEFT, Contraption::CounterClockwise), DOWN);
-        assert_eq!(rotate(DOWN, Contraption::CounterClockwise), LEFT);
+        assert_eq!(
+            rotate(Direction::Right, Contraption::CounterClockwise),
+            Direction::Up
+        );
+        assert_eq!(
+            rotate(Direction::Up, Contraption::CounterClockwise),
+            Direction::Right
+        );
+        assert_eq!(
+            rotate(Direction::Left, Contraption::CounterClockwise),
+            Direction::Down
+        );
+        assert_eq!(
+            rotate(Direction::Down, Contraption::CounterClockwise),
+            Direction::Left
+        );
 
         // "\"
-        assert_eq!(rotate(RIGHT, Contraption::Clockwise), DOWN);
-        assert_eq!(rotate(UP, Contraption::Clockwise), LEFT);
-        assert_eq!(rotate(LEFT, Contraption::Clockwise), UP);
-        assert_eq!(rotate(DOWN, Contraption::Clockwise), RIGHT);
+        assert_eq!(
+            rotate(Direction::Right, Contraption::Clockwise),
+            Direction::Down
+        );
+        assert_eq!(
+            rotate(Direction::Up, Contraption::Clockwise),
+            Direction::Left
+        );
+        assert_eq!(
+            rotate(Direction::Left, Contraption::Clockwise),
+            Direction::Up
+        );
+        assert_eq!(
+            rotate(Direction::Down, Contraption::Clockwise),
+            Direction::Right
+        );
     }
 
     #[test]
@@ -329,7 +334,36 @@ mod test {
 .-.-/..|..
 .|....-|.\
 ..//.|....";
-        assert_eq!(run(test_input.into(), false), 46);
-        assert_eq!(run(test_input.into(), true), 51);
+        let grid = ContraptionNetwork::parse(PuzzleInput::from(test_input)).unwrap();
+        assert_eq!(run(&grid, false), 46);
+        assert_eq!(run(&grid, true), 51);
+    }
+
+    #[test]
+    fn test_rectangular() {
+        let test_input = r".|...\....
+|.-.\.....
+.....|-...
+........|.
+..........
+.........\
+..../.\\..";
+        let grid = ContraptionNetwork::parse(PuzzleInput::from(test_input)).unwrap();
+        assert_eq!(run(&grid, false), 8);
+        assert_eq!(run(&grid, true), 26);
+
+        // A single row or column has no tiles between its top and bottom edges
+        let grid = ContraptionNetwork::parse(PuzzleInput::from("-.|")).unwrap();
+        assert_eq!(run(&grid, true), 3);
+        let grid = ContraptionNetwork::parse(PuzzleInput::from(".\n-\n.")).unwrap();
+        assert_eq!(run(&grid, true), 2);
+    }
+
+    #[test]
+    fn test_parse_error() {
+        assert_eq!(
+            Day16::parse("".into()).err(),
+            Some(ParseError::new(0, 0, "grid row"))
+        );
     }
 }
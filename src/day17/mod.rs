@@ -1,45 +1,79 @@
-use std::collections::{BinaryHeap, HashMap};
+//! Day 17: Clumsy Crucible
+//!
+//! The [`HeatGrid`] holds the heat loss of each city block.
+//! [`coolest_path`] lists the blocks of a coolest path, which [`render_path`] draws as arrows.
+//! [`CrucibleRules`] set how far a crucible moves straight, e.g. `"2-5".parse()`.
 
+use std::str::FromStr;
+
+use crate::{
+    astar, parse_token, Answer, Assumption, Direction, Grid, GridShape, Param, Params, ParseError,
+    Path, Position, Puzzle, PuzzleInput,
+};
 use either::Either;
 use itertools::Itertools;
-use num_integer::Roots;
 
-use crate::PuzzleInput;
+/// Heat loss of each city block
+pub type HeatGrid = Grid<u8>;
 
-type Position = usize;
+fn step(
+    step: usize,
+    direction: Direction,
+    position: Position,
+    heat_grid: &HeatGrid,
+) -> Option<Position> {
+    heat_grid.offset(position, direction.vector() * step as isize)
+}
 
-type Direction = u8;
-type HeatGrid = Vec<u8>;
+/// Moves a crucible may make: It moves straight for some blocks and then turns or stops
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CrucibleRules {
+    /// Fewest blocks to move before turning or stopping at the goal (at least 1)
+    pub min_straight: usize,
+    /// Most blocks to move without turning
+    pub max_straight: usize,
+    /// Whether the crucible may turn around instead of only left or right
+    pub allow_reverse: bool,
+}
 
-const RIGHT: Direction = 0;
-const UP: Direction = 1;
-const LEFT: Direction = 2;
-const DOWN: Direction = 3;
+impl CrucibleRules {
+    /// Crucible of part 1
+    pub const CRUCIBLE: Self = Self {
+        min_straight: 1,
+        max_straight: 3,
+        allow_reverse: false,
+    };
 
-fn left(d: Direction) -> Direction {
-    (d + 1) % 4
+    /// Ultra crucible of part 2
+    pub const ULTRA: Self = Self {
+        min_straight: 4,
+        max_straight: 10,
+        allow_reverse: false,
+    };
 }
 
-fn right(d: Direction) -> Direction {
-    (d + 3) % 4
-}
+impl FromStr for CrucibleRules {
+    type Err = ParseError;
 
-fn step(step: usize, direction: Direction, position: Position, size: usize) -> Option<Position> {
-    let (row, col) = num_integer::div_rem(position, size);
-    if match direction {
-        RIGHT => col + step <= size - 1,
-        UP => row >= step,
-        LEFT => col >= step,
-        _ => row + step <= size - 1,
-    } {
-        Some(match direction {
-            RIGHT => position + step,
-            UP => position - (step * size),
-            LEFT => position - step,
-            _ => position + (step * size),
+    /// Parse `MIN-MAX` straight blocks, optionally followed by `,reverse`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (straight, allow_reverse) = match s.strip_suffix(",reverse") {
+            Some(straight) => (straight, true),
+            None => (s, false),
+        };
+        let (min, max) = straight
+            .split_once('-')
+            .ok_or_else(|| ParseError::at_end(0, straight, "'-'"))?;
+        let min_straight = parse_token(0, s, min, "minimum straight blocks")?;
+        let max_straight = parse_token(0, s, max, "maximum straight blocks")?;
+        if min_straight == 0 || min_straight > max_straight {
+            return Err(ParseError::at(0, s, min, "1 <= MIN <= MAX"));
+        }
+        Ok(Self {
+            min_straight,
+            max_straight,
+            allow_reverse,
         })
-    } else {
-        None
     }
 }
 
@@ -50,13 +84,19 @@ struct Crucible {
 }
 
 impl Crucible {
-    fn maneuver(&self, size: usize, ultra: bool) -> impl Iterator<Item = Self> + '_ {
-        let (min_steps, max_steps) = if ultra { (4, 10) } else { (1, 3) };
-        [left(self.direction), right(self.direction)]
+    fn maneuver<'a>(
+        &'a self,
+        heat_grid: &'a HeatGrid,
+        rules: &CrucibleRules,
+    ) -> impl Iterator<Item = Self> + 'a {
+        let straight = rules.min_straight.max(1)..=rules.max_straight;
+        let reverse = rules.allow_reverse.then_some(self.direction.reverse());
+        [self.direction.turn_left(), self.direction.turn_right()]
             .into_iter()
+            .chain(reverse)
             .flat_map(move |direction| {
-                (min_steps..=max_steps).filter_map(move |s| {
-                    step(s, direction, self.position, size).map(|position| Crucible {
+                straight.clone().filter_map(move |s| {
+                    step(s, direction, self.position, heat_grid).map(|position| Crucible {
                         direction,
                         position,
                     })
@@ -64,173 +104,180 @@ impl Crucible {
             })
     }
 
-    fn line(&self, next_crucible: &Crucible, size: usize) -> impl Iterator<Item = Position> {
+    fn line(&self, next_crucible: &Crucible, width: usize) -> impl Iterator<Item = Position> {
         match next_crucible.direction {
-            RIGHT => Either::Left(self.position + 1..=next_crucible.position),
-            UP => Either::Right((next_crucible.position..=self.position - size).step_by(size)),
-            LEFT => Either::Left(next_crucible.position..=self.position - 1),
-            _ => Either::Right((self.position + size..=next_crucible.position).step_by(size)),
+            Direction::Right => Either::Left(self.position + 1..=next_crucible.position),
+            Direction::Up => {
+                Either::Right((next_crucible.position..=self.position - width).step_by(width))
+            }
+            Direction::Left => Either::Left(next_crucible.position..=self.position - 1),
+            Direction::Down => {
+                Either::Right((self.position + width..=next_crucible.position).step_by(width))
+            }
         }
         .into_iter()
     }
 }
 
-#[derive(Debug)]
-struct State {
-    crucible: Crucible,
-    heat: usize,
-    distance: usize,
-    vertical: bool,
+fn goal_distance(position: Position, heat_grid: &HeatGrid) -> usize {
+    let (row, col) = heat_grid.row_col(position);
+    (heat_grid.height() - 1 - row) + (heat_grid.width() - 1 - col)
 }
 
-fn goal_distance(position: Position, size: usize) -> usize {
-    let (row, col) = num_integer::div_rem(position, size);
-    let goal = size - 1;
-    (goal - row) + (goal - col)
+/// Coolest path of crucibles from the top left to the bottom right block.
+/// Each crucible on the path is where it stops after moving in its direction.
+fn coolest_maneuvers(heat_grid: &HeatGrid, rules: &CrucibleRules) -> Option<Path<Crucible>> {
+    let width = heat_grid.width();
+    // Start in top-left heading either way, so that the first move may go right or down
+    let starts = [Direction::Right, Direction::Down].map(|direction| Crucible {
+        position: 0,
+        direction,
+    });
+    let successors = |crucible: &Crucible| {
+        crucible
+            .maneuver(heat_grid, rules)
+            .map(|next_crucible| {
+                let heat = crucible
+                    .line(&next_crucible, width)
+                    .map(|p| heat_grid[p] as usize)
+                    .sum();
+                (next_crucible, heat)
+            })
+            .collect_vec()
+    };
+    // The distance is a lower bound of the remaining heat if every block loses at least 1 heat
+    astar(
+        starts,
+        successors,
+        |crucible| goal_distance(crucible.position, heat_grid),
+        |crucible| crucible.position == heat_grid.len() - 1,
+    )
 }
-impl State {
-    fn new(crucible: Crucible, heat: usize, size: usize) -> Self {
-        Self {
-            heat,
-            distance: goal_distance(crucible.position, size),
-            vertical: crucible.direction % 2 == 1,
-            crucible,
-        }
-    }
+
+/// Block entered on a coolest path
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PathStep {
+    pub position: Position,
+    /// Direction of the move into the block
+    pub direction: Direction,
+    /// Heat lost from the start up to and including the block
+    pub heat: usize,
 }
 
-impl PartialEq for State {
-    fn eq(&self, other: &Self) -> bool {
-        self.heat == other.heat
-            && self.crucible.position == other.crucible.position
-            && self.vertical == other.vertical
-    }
+/// Blocks entered one by one on a coolest path from the top left to the bottom right block,
+/// or `None` if the bottom right block cannot be reached
+pub fn coolest_path(heat_grid: &HeatGrid, rules: &CrucibleRules) -> Option<Vec<PathStep>> {
+    let width = heat_grid.width();
+    let maneuvers = coolest_maneuvers(heat_grid, rules)?;
+    let mut heat = 0;
+    let steps = maneuvers
+        .nodes
+        .iter()
+        .tuple_windows()
+        .flat_map(|(crucible, next_crucible)| {
+            let blocks = crucible.line(next_crucible, width).count();
+            (1..=blocks).map(|s| {
+                let direction = next_crucible.direction;
+                let position = step(s, direction, crucible.position, heat_grid).unwrap();
+                (position, direction)
+            })
+        })
+        .map(|(position, direction)| {
+            heat += heat_grid[position] as usize;
+            PathStep {
+                position,
+                direction,
+                heat,
+            }
+        })
+        .collect();
+    Some(steps)
 }
-impl Eq for State {}
 
-impl Ord for State {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        (self.heat + self.distance)
-            .cmp(&(other.heat + other.distance))
-            .reverse()
-            .then_with(|| self.vertical.cmp(&other.vertical))
+/// Heat grid with the blocks of `path` drawn as arrows of their direction,
+/// like the illustration of the puzzle
+pub fn render_path(heat_grid: &HeatGrid, path: &[PathStep]) -> String {
+    let mut cells = heat_grid.map(|&heat| char::from(b'0' + heat));
+    for path_step in path {
+        cells[path_step.position] = path_step.direction.arrow();
     }
+    cells.to_string()
+}
+
+/// Least heat loss from the top left to the bottom right block under `rules`,
+/// or `None` if the bottom right block cannot be reached
+pub fn least_heat_loss(heat_grid: &HeatGrid, rules: &CrucibleRules) -> Option<usize> {
+    coolest_maneuvers(heat_grid, rules).map(|path| path.cost)
+}
+
+fn run(heat_grid: &HeatGrid, part2: bool) -> usize {
+    let rules = if part2 {
+        CrucibleRules::ULTRA
+    } else {
+        CrucibleRules::CRUCIBLE
+    };
+    least_heat_loss(heat_grid, &rules).expect("bottom right block should be reachable")
+}
+
+/// Least heat loss of a crucible from the top left to the bottom right block
+pub fn part1(heat_grid: &HeatGrid) -> usize {
+    run(heat_grid, false)
+}
+
+/// Least heat loss of an ultra crucible from the top left to the bottom right block
+pub fn part2(heat_grid: &HeatGrid) -> usize {
+    run(heat_grid, true)
 }
 
-impl PartialOrd for State {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        Some(self.cmp(other))
+/// Least heat loss under the `rules` parameter of a part
+fn least_heat_loss_answer(heat_grid: &HeatGrid, params: &Params) -> Answer {
+    match least_heat_loss(heat_grid, &params.get("rules")) {
+        Some(heat) => heat.into(),
+        None => Answer::Unsolved("bottom right block is unreachable".into()),
     }
 }
 
-fn run(input: PuzzleInput, part2: bool) -> usize {
-    let heat_grid: HeatGrid = input
-        .flat_map(|line| {
-            line.chars()
-                .map(move |c| c.to_digit(10).unwrap() as u8)
-                .collect_vec()
-        })
-        .collect();
-    let size = heat_grid.len().sqrt();
+pub struct Day17;
 
-    // Approach: Find the shortest/coolest path on a weighted graph/heat-map
-    let mut min_heat_by_crucible: HashMap<Crucible, usize> = HashMap::new();
-    // Start in top-left with goal at bottom-right
-    let mut frontier: BinaryHeap<State> = [RIGHT, DOWN]
-        .into_iter()
-        .map(|direction| Crucible {
-            position: 0,
-            direction,
-        })
-        .map(|crucible| State::new(crucible, 0, size))
-        .collect();
-    while let Some(State {
-        crucible,
-        heat,
-        distance,
-        vertical: _,
-    }) = frontier.pop()
-    {
-        if min_heat_by_crucible
-            .get(&crucible)
-            .is_some_and(|min_heat| heat >= *min_heat)
-        {
-            // Do not re-walk non-imporoving heat-paths
-            continue;
-        } else {
-            min_heat_by_crucible.insert(crucible.clone(), heat);
-        }
+impl Puzzle for Day17 {
+    type Input = HeatGrid;
 
-        if distance == 0 {
-            // Min-Heat path has been found.
-            // (This requires no 0-heat paths, i.e., all `heat_grid` numbers must be >= 1)
-            break;
-        }
+    const TITLE: &'static str = "Clumsy Crucible";
 
-        for next_crucible in crucible.maneuver(size, part2) {
-            let next_heat = heat
-                + crucible
-                    .line(&next_crucible, size)
-                    .map(|p| heat_grid[p])
-                    .sum::<u8>() as usize;
-            if !min_heat_by_crucible
-                .get(&next_crucible)
-                .is_some_and(|min_heat| next_heat >= *min_heat)
-            {
-                // Add candidate with `next_crucible` to frontier
-                frontier.push(State::new(next_crucible, next_heat, size));
-            }
-        }
-        if cfg!(test) {
-            // Print state of search
-            println!("Frontier: {}", frontier.len());
-            for row in 0..size {
-                let overview = (0..size)
-                    .map(|col| {
-                        [RIGHT, UP, LEFT, DOWN]
-                            .into_iter()
-                            .filter_map(|d| {
-                                min_heat_by_crucible.get(&Crucible {
-                                    position: row * size + col,
-                                    direction: d,
-                                })
-                            })
-                            .min()
-                            .map(|n| n.to_string())
-                            .unwrap_or("?".into())
-                    })
-                    .map(|s| format!("{:3}", s))
-                    .collect_vec();
-                println!("{:?}", overview);
-            }
+    const PARAMS: &'static [Param] = &[Param::per_part::<CrucibleRules>(
+        "rules",
+        "straight blocks between turns as MIN-MAX, optionally followed by ',reverse'",
+        ["1-3", "4-10"],
+    )];
+
+    fn parse(input: PuzzleInput) -> Result<Self::Input, ParseError> {
+        let heat_grid = Grid::parse(input.enumerate(), "0123456789", |c| c as u8 - b'0')?;
+        if heat_grid.is_empty() {
+            return Err(ParseError::new(0, 0, "grid row"));
         }
+        Ok(heat_grid)
     }
 
-    // Get minimum heat for bottom-right goal
-    [RIGHT, DOWN]
-        .into_iter()
-        .filter_map(|direction| {
-            min_heat_by_crucible.get(&Crucible {
-                position: (size * size) - 1,
-                direction,
-            })
-        })
-        .min()
-        .copied()
-        .unwrap()
-}
+    fn part1(heat_grid: &Self::Input, params: &Params) -> Answer {
+        least_heat_loss_answer(heat_grid, params)
+    }
 
-pub fn solution(input: PuzzleInput, part2: bool) -> String {
-    run(input, part2).to_string()
+    fn part2(heat_grid: &Self::Input, params: &Params) -> Answer {
+        least_heat_loss_answer(heat_grid, params)
+    }
+
+    fn assumptions(heat_grid: &Self::Input, _part2: bool, _params: &Params) -> Vec<Assumption> {
+        vec![Assumption::new(
+            "every block loses at least 1 heat, so the distance to the goal bounds the remaining heat",
+            heat_grid.cells().iter().all(|&heat| heat >= 1),
+        )]
+    }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
-
-    #[test]
-    fn test_run() {
-        let test_input = r"2413432311323
+    const EXAMPLE: &str = r"2413432311323
 3215453535623
 3255245654254
 3446585845452
@@ -244,7 +291,118 @@ mod test {
 2546548887735
 4322674655533
 ";
-        assert_eq!(run(test_input.into(), false), 102);
-        assert_eq!(run(test_input.into(), true), 94);
+
+    #[test]
+    fn test_run() {
+        let heat_grid = Day17::parse(EXAMPLE.into()).unwrap();
+        assert_eq!(run(&heat_grid, false), 102);
+        assert_eq!(run(&heat_grid, true), 94);
+    }
+
+    #[test]
+    fn test_coolest_path() {
+        let heat_grid = Day17::parse(EXAMPLE.into()).unwrap();
+        let path = coolest_path(&heat_grid, &CrucibleRules::CRUCIBLE).unwrap();
+        assert_eq!(
+            path[0],
+            PathStep {
+                position: 1,
+                direction: Direction::Right,
+                heat: 4
+            }
+        );
+        assert_eq!(
+            path.last(),
+            Some(&PathStep {
+                position: heat_grid.len() - 1,
+                direction: Direction::Right,
+                heat: 102
+            })
+        );
+        assert_eq!(
+            render_path(&heat_grid, &path),
+            r"2>>34^>>>1323
+32v>>>35v5623
+32552456v>>54
+3446585845v52
+4546657867v>6
+14385987984v4
+44578769877v6
+36378779796v>
+465496798688v
+456467998645v
+12246868655<v
+25465488877v5
+43226746555v>
+"
+        );
+        assert_eq!(
+            coolest_path(
+                &Day17::parse("11\n11".into()).unwrap(),
+                &CrucibleRules::ULTRA
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn test_rectangular() {
+        let test_input = r"111111111111
+999999999991
+999999999991
+999999999991
+999999999991
+";
+        let heat_grid = Day17::parse(test_input.into()).unwrap();
+        assert_eq!(run(&heat_grid, false), 59);
+        assert_eq!(run(&heat_grid, true), 71);
+    }
+
+    #[test]
+    fn test_rules() {
+        let heat_grid = Day17::parse(EXAMPLE.into()).unwrap();
+        let least_heat_loss = |rules: &str| least_heat_loss(&heat_grid, &rules.parse().unwrap());
+        assert_eq!(least_heat_loss("1-3"), Some(102));
+        assert_eq!(least_heat_loss("4-10"), Some(94));
+        assert_eq!(least_heat_loss("2-5"), Some(101));
+        assert_eq!(least_heat_loss("1-3,reverse"), Some(101));
+        assert_eq!(least_heat_loss("1-1"), Some(133));
+
+        assert_eq!("4-10".parse(), Ok(CrucibleRules::ULTRA));
+        assert_eq!(
+            "2-5,reverse".parse(),
+            Ok(CrucibleRules {
+                min_straight: 2,
+                max_straight: 5,
+                allow_reverse: true
+            })
+        );
+        assert_eq!(
+            "3".parse::<CrucibleRules>(),
+            Err(ParseError::new(0, 1, "'-'"))
+        );
+        assert_eq!(
+            "5-2".parse::<CrucibleRules>(),
+            Err(ParseError::new(0, 0, "1 <= MIN <= MAX"))
+        );
+        assert!("0-3".parse::<CrucibleRules>().is_err());
+    }
+
+    #[test]
+    fn test_assumptions() {
+        let holds = |input| {
+            let params = Params::defaults(Day17::PARAMS, 1);
+            Day17::assumptions(&Day17::parse(input).unwrap(), false, &params)[0].holds
+        };
+        assert!(holds("19\n91".into()));
+        assert!(!holds("10\n91".into()));
+    }
+
+    #[test]
+    fn test_parse_error() {
+        assert_eq!(
+            Day17::parse("".into()).err(),
+            Some(ParseError::new(0, 0, "grid row"))
+        );
     }
 }
@@ -1,3 +1,7 @@
+//! Day 18: Lavaduct Lagoon
+//!
+//! Each [`DigInstruction`] holds the direction, distance and color of a trench segment.
+
 use std::{collections::BTreeMap, ops::Range};
 
 mod ingressline;
@@ -11,19 +15,76 @@ use ingressline::IngressLine;
 
 use itertools::Itertools;
 
-use crate::PuzzleInput;
+use crate::{parse_token, Answer, Direction, Params, ParseError, Point, Puzzle, PuzzleInput};
 
 use self::ingressline::IngressLineIdx;
 
-type Direction = u8;
-const RIGHT: Direction = 0;
-const UP: Direction = 1;
-const LEFT: Direction = 2;
-const DOWN: Direction = 3;
-
 #[derive(Debug, Clone)]
 struct Color(u32);
 
+impl Color {
+    /// Direction encoded in the last hexadecimal digit
+    fn direction(&self) -> Direction {
+        match self.0 & 0xf {
+            0 => Direction::Right,
+            1 => Direction::Down,
+            2 => Direction::Left,
+            _ => Direction::Up,
+        }
+    }
+
+    /// Distance encoded in the first five hexadecimal digits
+    fn steps(&self) -> i32 {
+        (self.0 >> 4) as i32
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DigInstruction {
+    direction: Direction,
+    steps: i32,
+    color: Color,
+}
+
+impl DigInstruction {
+    /// Direction and steps, which part 2 takes from the color
+    fn movement(&self, part2: bool) -> (Direction, i32) {
+        if part2 {
+            (self.color.direction(), self.color.steps())
+        } else {
+            (self.direction, self.steps)
+        }
+    }
+}
+
+/// Check that the trench of both parts is a loop with vertical lines
+fn check_dig_plan(dig_plan: &[DigInstruction]) -> Result<(), ParseError> {
+    for part2 in [false, true] {
+        let moves = dig_plan
+            .iter()
+            .map(|instruction| instruction.movement(part2));
+        let expected = if part2 { " by color" } else { "" };
+        if !moves.clone().any(|(d, _)| d.is_vertical()) {
+            return Err(ParseError::new(
+                dig_plan.len(),
+                0,
+                format!("up or down instruction{expected}"),
+            ));
+        }
+        let end = moves.fold(Point::default(), |point, (d, s)| {
+            point + d.vector() * s as isize
+        });
+        if end != Point::default() {
+            return Err(ParseError::new(
+                dig_plan.len(),
+                0,
+                format!("trench back to its start{expected}"),
+            ));
+        }
+    }
+    Ok(())
+}
+
 #[derive(Debug, Clone)]
 pub struct VerticalTrenchLine {
     row: i32,
@@ -36,8 +97,8 @@ impl VerticalTrenchLine {
     /// Range of vertical rows. (The includes an additional tail.)
     fn row_range(&self) -> Range<i32> {
         match self.direction {
-            DOWN => self.row..self.row + self.length + 1,
-            UP => self.row - self.length..self.row + 1,
+            Direction::Down => self.row..self.row + self.length + 1,
+            Direction::Up => self.row - self.length..self.row + 1,
             _ => panic!("VerticalTrenchLine must be vertical"),
         }
     }
@@ -66,7 +127,7 @@ fn area(vertical_trench_lines: &[VerticalTrenchLine]) -> usize {
                 #[cfg(feature = "plot")]
                 {
                     let row_range = line.row_range();
-                    if line.direction == DOWN {
+                    if line.direction == Direction::Down {
                         trench_points.push((row_range.start, line.col + 1));
                         trench_points.push((row_range.end, line.col + 1));
                     } else {
@@ -168,56 +229,22 @@ fn area(vertical_trench_lines: &[VerticalTrenchLine]) -> usize {
     area
 }
 
-fn run(input: PuzzleInput, part2: bool) -> usize {
-    let vertical_trench_lines = input
-        .map(|line| {
-            let (direction, steps, color) = line.split_whitespace().collect_tuple().unwrap();
-
-            if part2 {
-                (
-                    match color[7..8].parse::<u8>().unwrap() {
-                        0 => RIGHT,
-                        1 => DOWN,
-                        2 => LEFT,
-                        _ => UP,
-                    },
-                    color[2..7]
-                        .chars()
-                        .flat_map(|c| c.to_digit(16))
-                        .map(|digit| digit as i32)
-                        .reduce(|n, digit| 16 * n + digit)
-                        .unwrap(),
-                )
-            } else {
-                (
-                    "RUL".find(direction).map(|d| d as u8).unwrap_or(DOWN),
-                    steps.parse::<i32>().unwrap(),
-                )
-            }
-        })
-        .scan((0_i32, 0_i32), |(row, col), (d, s)| {
-            let vertical = d % 2 == 1;
-            let line = if vertical {
+fn run(dig_plan: &[DigInstruction], part2: bool) -> usize {
+    let vertical_trench_lines = dig_plan
+        .iter()
+        .map(|instruction| instruction.movement(part2))
+        .scan(Point::default(), |point, (d, s)| {
+            let line = if d.is_vertical() {
                 Some(VerticalTrenchLine {
-                    row: *row,
-                    col: *col,
+                    row: point.row as i32,
+                    col: point.col as i32,
                     length: s,
                     direction: d,
                 })
             } else {
                 None
             };
-
-            *row = match d {
-                UP => *row - s,
-                DOWN => *row + s,
-                _ => *row,
-            };
-            *col = match d {
-                RIGHT => *col + s,
-                LEFT => *col - s,
-                _ => *col,
-            };
+            *point += d.vector() * s as isize;
             Some(line)
         })
         .flatten()
@@ -226,8 +253,70 @@ fn run(input: PuzzleInput, part2: bool) -> usize {
     area(&vertical_trench_lines)
 }
 
-pub fn solution(input: PuzzleInput, part2: bool) -> String {
-    run(input, part2).to_string()
+fn parse_hex(line: usize, text: &str, hex: &str) -> Result<u32, ParseError> {
+    u32::from_str_radix(hex, 16).map_err(|_| ParseError::at(line, text, hex, "hex color"))
+}
+
+/// Cubic meters of lava the lagoon of the dig plan holds
+pub fn part1(dig_plan: &[DigInstruction]) -> usize {
+    run(dig_plan, false)
+}
+
+/// Cubic meters of lava the lagoon holds when the colors encode the dig plan
+pub fn part2(dig_plan: &[DigInstruction]) -> usize {
+    run(dig_plan, true)
+}
+
+pub struct Day18;
+
+impl Puzzle for Day18 {
+    type Input = Vec<DigInstruction>;
+
+    const TITLE: &'static str = "Lavaduct Lagoon";
+
+    fn parse(input: PuzzleInput) -> Result<Self::Input, ParseError> {
+        let dig_plan: Vec<DigInstruction> = input
+            .enumerate()
+            .filter(|(_, text)| !text.is_empty())
+            .map(|(line, text)| {
+                let mut tokens = text.split_whitespace();
+                let mut next_token = |expected| {
+                    tokens
+                        .next()
+                        .ok_or_else(|| ParseError::at_end(line, &text, expected))
+                };
+                let (direction, steps, color) = (
+                    next_token("direction")?,
+                    next_token("steps")?,
+                    next_token("color")?,
+                );
+                let hex = color
+                    .strip_prefix("(#")
+                    .and_then(|c| c.strip_suffix(')'))
+                    .filter(|hex| hex.len() == 6)
+                    .ok_or_else(|| ParseError::at(line, &text, color, "'(#<6 hex digits>)'"))?;
+                Ok(DigInstruction {
+                    direction: direction
+                        .parse()
+                        .ok()
+                        .and_then(Direction::from_letter)
+                        .ok_or_else(|| ParseError::at(line, &text, direction, "one of \"RULD\""))?,
+                    steps: parse_token(line, &text, steps, "steps")?,
+                    color: Color(parse_hex(line, &text, hex)?),
+                })
+            })
+            .try_collect()?;
+        check_dig_plan(&dig_plan)?;
+        Ok(dig_plan)
+    }
+
+    fn part1(dig_plan: &Self::Input, _params: &Params) -> Answer {
+        part1(dig_plan).into()
+    }
+
+    fn part2(dig_plan: &Self::Input, _params: &Params) -> Answer {
+        part2(dig_plan).into()
+    }
 }
 
 #[cfg(test)]
@@ -250,7 +339,25 @@ R 2 (#7807d2)
 U 3 (#a77fa3)
 L 2 (#015232)
 U 2 (#7a21e3)";
-        assert_eq!(run(test_input.into(), false), 62);
-        assert_eq!(run(test_input.into(), true), 952408144115);
+        let dig_plan = Day18::parse(test_input.into()).unwrap();
+        assert_eq!(run(&dig_plan, false), 62);
+        assert_eq!(run(&dig_plan, true), 952408144115);
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let err = |input: &'static str| Day18::parse(input.into()).err();
+        assert_eq!(
+            err(""),
+            Some(ParseError::new(0, 0, "up or down instruction"))
+        );
+        assert_eq!(
+            err("R 2 (#000021)\nD 2 (#000023)"),
+            Some(ParseError::new(2, 0, "trench back to its start"))
+        );
+        assert_eq!(
+            err("R 2 (#000020)\nD 2 (#000020)\nL 2 (#000020)\nU 2 (#000020)"),
+            Some(ParseError::new(4, 0, "up or down instruction by color"))
+        );
     }
 }
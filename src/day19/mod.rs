@@ -1,31 +1,41 @@
+//! Day 19: Aplenty
+//!
+//! The [`System`] holds the workflows and parts; [`System::constraints`] gives the
+//! rating ranges which the workflows accept.
+
 use itertools::Itertools;
 use regex::Regex;
 
-use crate::{range_intersect, split_by_empty_line, PuzzleInput};
+use crate::{parse_token, range_intersect, Answer, Param, Params, ParseError, Puzzle, PuzzleInput};
 
 use std::{
     collections::{HashMap, HashSet},
-    ops::Range,
+    ops::{Range, RangeInclusive},
 };
 
-type Num = usize;
-type Category = usize;
-
-const NUM_MAX: Num = 4000;
-const NUM_MIN: Num = 1;
+/// Rating number
+pub type Num = usize;
+/// Index of the `x`, `m`, `a` or `s` rating
+pub type Category = usize;
 
+/// Rating ranges of each category which together lead to acceptance
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
-struct Constraint {
+pub struct Constraint {
     variables: [Range<Num>; 4],
 }
 
 impl Constraint {
-    fn empty() -> Self {
+    fn empty(ratings: &Range<Num>) -> Self {
         Constraint {
-            variables: array_init::array_init(|_| NUM_MIN..NUM_MAX + 1),
+            variables: array_init::array_init(|_| ratings.clone()),
         }
     }
-    fn accept(&self, props: &[Num; 4]) -> bool {
+    /// Ranges by category
+    pub fn ranges(&self) -> &[Range<Num>; 4] {
+        &self.variables
+    }
+
+    pub fn accept(&self, props: &Part) -> bool {
         props
             .iter()
             .zip_eq(self.variables.iter())
@@ -42,14 +52,18 @@ impl Constraint {
         }
     }
 
-    fn count_combinations(&self) -> usize {
+    /// Number of parts within the ranges
+    pub fn count_combinations(&self) -> usize {
         self.variables.iter().map(|r| r.end - r.start).product()
     }
 }
 
-fn find_constrains(rules: &HashMap<String, Vec<RuleSegment>>) -> Vec<Constraint> {
+fn find_constrains(
+    rules: &HashMap<String, Vec<RuleSegment>>,
+    ratings: &Range<Num>,
+) -> Vec<Constraint> {
     let mut constraints: HashSet<Constraint> = HashSet::new();
-    let mut constraint_stack = vec![("in".to_string(), 0, Constraint::empty())];
+    let mut constraint_stack = vec![("in".to_string(), 0, Constraint::empty(ratings))];
 
     while let Some((rule_label, segment_index, constraint)) = constraint_stack.pop() {
         match &rules[&rule_label][segment_index] {
@@ -62,9 +76,9 @@ fn find_constrains(rules: &HashMap<String, Vec<RuleSegment>>) -> Vec<Constraint>
             }
             RuleSegment::Condition(cat, less_than, num, literal) => {
                 let (conjunction, else_conjunction) = if *less_than {
-                    (NUM_MIN..*num, *num..NUM_MAX + 1)
+                    (ratings.start..*num, *num..ratings.end)
                 } else {
-                    (*num + 1..NUM_MAX + 1, NUM_MIN..*num + 1)
+                    (*num + 1..ratings.end, ratings.start..*num + 1)
                 };
                 if let Some(else_constraint) = constraint.clone().tighten(*cat, &else_conjunction) {
                     constraint_stack.push((rule_label.clone(), segment_index + 1, else_constraint));
@@ -103,80 +117,174 @@ enum RuleSegment {
     Condition(Category, bool, Num, RuleLiteral),
 }
 
-fn run(input: PuzzleInput, part2: bool) -> usize {
-    let [rule_pattern, segment_pattern, num_pattern] =
-        [r"(\w+)\{(.*?)\}", r"([xmas])(.)(\d+):(\w+)", r"\d+"].map(|r| Regex::new(r).unwrap());
-    split_by_empty_line!(input)
-        .scan(None as Option<Vec<Constraint>>, |constraints, lines| {
-            if let Some(consts) = constraints.take() {
-                let parts: Vec<[Num; 4]> = lines
-                    .map(|l| {
-                        array_init::from_iter(
-                            num_pattern
-                                .find_iter(&l)
-                                .map(|c| c.as_str().parse().expect("part num")),
-                        )
-                        .unwrap()
-                    })
-                    .collect_vec();
-                Some(Some(
-                    parts
-                        .into_iter()
-                        .filter(|p| consts.iter().any(|c| c.accept(p)))
-                        .map(|p| p.iter().sum::<usize>())
-                        .sum(),
-                ))
-            } else {
-                let rules: HashMap<String, Vec<RuleSegment>> = lines
-                    .map(|line| {
-                        let caps = rule_pattern.captures(&line).expect("rule line");
-                        (
-                            caps[1].to_string(),
-                            caps[2]
-                                .split(',')
-                                .map(|p| {
-                                    segment_pattern
-                                        .captures(p)
-                                        .map(|c| {
-                                            RuleSegment::Condition(
-                                                "xmas".find(&c[1]).unwrap(),
-                                                &c[2] == "<",
-                                                c[3].parse().expect("condition num"),
-                                                match &c[4] {
-                                                    "A" => RuleLiteral::Accept,
-                                                    "R" => RuleLiteral::Reject,
-                                                    l => RuleLiteral::Link(l.to_string()),
-                                                },
-                                            )
-                                        })
-                                        .unwrap_or_else(|| {
-                                            RuleSegment::Literal(match p {
-                                                "A" => RuleLiteral::Accept,
-                                                "R" => RuleLiteral::Reject,
-                                                _ => RuleLiteral::Link(p.to_string()),
-                                            })
-                                        })
-                                })
-                                .collect(),
-                        )
-                    })
-                    .collect();
-                let c = find_constrains(&rules);
-                if part2 {
-                    Some(Some(count_combinations(&c)))
-                } else {
-                    *constraints = Some(c);
-                    Some(None)
+/// Ratings by category
+pub type Part = [Num; 4];
+
+/// Workflow rules by label and the part ratings to sort
+pub struct System {
+    rules: HashMap<String, Vec<RuleSegment>>,
+    parts: Vec<Part>,
+}
+
+fn parse_rule_literal(label: &str) -> RuleLiteral {
+    match label {
+        "A" => RuleLiteral::Accept,
+        "R" => RuleLiteral::Reject,
+        l => RuleLiteral::Link(l.to_string()),
+    }
+}
+
+impl System {
+    fn parse(input: PuzzleInput) -> Result<Self, ParseError> {
+        let [rule_pattern, segment_pattern, label_pattern, part_pattern] = [
+            r"^(\w+)\{(.*)\}$",
+            r"^([xmas])([<>])(\d+):(\w+)$",
+            r"^\w+$",
+            r"^\{x=(\d+),m=(\d+),a=(\d+),s=(\d+)\}$",
+        ]
+        .map(|r| Regex::new(r).unwrap());
+        let groups = input.enumerate().group_by(|(_, text)| text.is_empty());
+        let mut blocks = groups.into_iter().filter(|(empty, _)| !empty);
+
+        let mut rules: HashMap<String, Vec<RuleSegment>> = HashMap::new();
+        let mut links: Vec<(usize, String)> = vec![];
+        for (line, text) in blocks.next().map(|(_, lines)| lines).into_iter().flatten() {
+            let caps = rule_pattern
+                .captures(&text)
+                .ok_or_else(|| ParseError::new(line, 0, "'<label>{<rules>}' workflow"))?;
+            let segments = caps[2]
+                .split(',')
+                .map(|p| {
+                    if let Some(c) = segment_pattern.captures(p) {
+                        Ok(RuleSegment::Condition(
+                            "xmas".find(&c[1]).unwrap(),
+                            &c[2] == "<",
+                            parse_token(line, &text, &c[3], "condition num")?,
+                            parse_rule_literal(&c[4]),
+                        ))
+                    } else if label_pattern.is_match(p) {
+                        Ok(RuleSegment::Literal(parse_rule_literal(p)))
+                    } else {
+                        Err(ParseError::at(
+                            line,
+                            &text,
+                            p,
+                            "'<category><op><num>:<label>' rule or label",
+                        ))
+                    }
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            links.extend(segments.iter().filter_map(|segment| match segment {
+                RuleSegment::Literal(RuleLiteral::Link(label))
+                | RuleSegment::Condition(_, _, _, RuleLiteral::Link(label)) => {
+                    Some((line, label.clone()))
                 }
-            }
-        })
+                _ => None,
+            }));
+            rules.insert(caps[1].to_string(), segments);
+        }
+        if !rules.contains_key("in") {
+            return Err(ParseError::new(0, 0, "'in' workflow"));
+        }
+        if let Some((line, label)) = links.into_iter().find(|(_, l)| !rules.contains_key(l)) {
+            return Err(ParseError::new(
+                line,
+                0,
+                format!("workflow '{label}' to exist"),
+            ));
+        }
+
+        let parts: Vec<Part> = blocks
+            .next()
+            .map(|(_, lines)| lines)
+            .into_iter()
+            .flatten()
+            .map(|(line, text)| {
+                let caps = part_pattern.captures(&text).ok_or_else(|| {
+                    ParseError::new(line, 0, "'{x=<num>,m=<num>,a=<num>,s=<num>}'")
+                })?;
+                let mut part = [0; 4];
+                for (rating, c) in part.iter_mut().zip(caps.iter().skip(1).flatten()) {
+                    *rating = parse_token(line, &text, c.as_str(), "part rating")?;
+                }
+                Ok(part)
+            })
+            .try_collect()?;
+        Ok(Self { rules, parts })
+    }
+
+    /// Disjoint constraints of all parts with ratings in `ratings` which the workflows accept
+    pub fn constraints_within(&self, ratings: RangeInclusive<Num>) -> Vec<Constraint> {
+        find_constrains(&self.rules, &(*ratings.start()..*ratings.end() + 1))
+    }
+
+    pub fn parts(&self) -> &[Part] {
+        &self.parts
+    }
+}
+
+/// Number of rating combinations within `ratings` which the workflows accept
+pub fn accepted_combinations(system: &System, ratings: RangeInclusive<Num>) -> usize {
+    count_combinations(&system.constraints_within(ratings))
+}
+
+/// Sum of the ratings of all accepted parts
+pub fn part1(system: &System) -> usize {
+    // Constraints up to the highest rating of any part decide all parts
+    let max_rating = system
+        .parts
+        .iter()
         .flatten()
-        .next()
-        .unwrap()
+        .copied()
+        .max()
+        .unwrap_or_default();
+    let constraints = system.constraints_within(0..=max_rating);
+    system
+        .parts
+        .iter()
+        .filter(|p| constraints.iter().any(|c| c.accept(p)))
+        .map(|p| p.iter().sum::<usize>())
+        .sum()
+}
+
+/// Number of rating combinations from 1 to 4000 which the workflows accept
+pub fn part2(system: &System) -> usize {
+    let params = Params::defaults(Day19::PARAMS, 2);
+    accepted_combinations(system, params.get("min_rating")..=params.get("max_rating"))
 }
 
-pub fn solution(input: PuzzleInput, part2: bool) -> String {
-    run(input, part2).to_string()
+pub struct Day19;
+
+impl Puzzle for Day19 {
+    type Input = System;
+
+    const TITLE: &'static str = "Aplenty";
+
+    const PARAMS: &'static [Param] = &[
+        Param::new::<Num>(
+            "min_rating",
+            "lowest rating of the combinations of part 2",
+            "1",
+        ),
+        Param::new::<Num>(
+            "max_rating",
+            "highest rating of the combinations of part 2",
+            "4000",
+        ),
+    ];
+
+    fn parse(input: PuzzleInput) -> Result<Self::Input, ParseError> {
+        System::parse(input)
+    }
+
+    fn part1(system: &Self::Input, _params: &Params) -> Answer {
+        part1(system).into()
+    }
+
+    fn part2(system: &Self::Input, params: &Params) -> Answer {
+        let ratings = params.get("min_rating")..=params.get("max_rating");
+        accepted_combinations(system, ratings).into()
+    }
 }
 
 #[cfg(test)]
@@ -204,7 +312,18 @@ hdj{m>838:A,pv}
 {x=2127,m=1623,a=2188,s=1013}
 
 ";
-        assert_eq!(run(test_input.into(), false), 19114);
-        assert_eq!(run(test_input.into(), true), 167409079868000);
+        let system = Day19::parse(test_input.into()).unwrap();
+        assert_eq!(part1(&system), 19114);
+        assert_eq!(part2(&system), 167409079868000);
+
+        let overrides = [
+            "min_rating=1000".parse().unwrap(),
+            "max_rating=3000".parse().unwrap(),
+        ];
+        let params = Params::new(Day19::PARAMS, 2, &overrides).unwrap();
+        assert_eq!(
+            Day19::part2(&system, &params),
+            Answer::from(8114744891619_usize)
+        );
     }
 }
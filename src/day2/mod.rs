@@ -1,20 +1,30 @@
+//! Day 2: Cube Conundrum
+//!
+//! Each [`Game`] holds the cube sets revealed in it.
+
 use std::collections::HashMap;
 
 use itertools::Itertools;
 
-use crate::PuzzleInput;
+use crate::{parse_token, Answer, Param, Params, ParseError, Puzzle, PuzzleInput};
 
 struct Bag(HashMap<String, usize>);
 
 impl Bag {
-    fn new(list: &str) -> Self {
-        Bag(list
+    fn parse(line: usize, text: &str, list: &str) -> Result<Self, ParseError> {
+        Ok(Bag(list
             .split(',')
             .map(|c| {
-                let (count, cube_type) = c.trim().splitn(2, ' ').collect_tuple().unwrap();
-                (cube_type.into(), count.parse().unwrap())
+                let (count, cube_type) = c
+                    .trim()
+                    .split_once(' ')
+                    .ok_or_else(|| ParseError::at(line, text, c, "'<count> <color>'"))?;
+                Ok((
+                    cube_type.into(),
+                    parse_token(line, text, count, "cube count")?,
+                ))
             })
-            .collect())
+            .collect::<Result<_, _>>()?))
     }
 
     fn subset_of(&self, bag: &Bag) -> bool {
@@ -42,19 +52,29 @@ impl Bag {
     }
 }
 
-struct Game {
+pub struct Game {
     id: usize,
     minimum_bag: Bag,
 }
 
 impl Game {
-    fn new(line: &str) -> Self {
-        let (label, listing) = line.splitn(2, ':').collect_tuple().unwrap();
-        let hands = listing.split(';').map(Bag::new);
-        Self {
-            id: label.splitn(2, ' ').last().unwrap().parse().unwrap(),
-            minimum_bag: hands.reduce(|min_bag, bag| min_bag.grow_to(&bag)).unwrap(),
-        }
+    fn parse(line: usize, text: &str) -> Result<Self, ParseError> {
+        let (label, listing) = text
+            .split_once(':')
+            .ok_or_else(|| ParseError::at_end(line, text, "':' after game label"))?;
+        let id = label
+            .split_once(' ')
+            .ok_or_else(|| ParseError::new(line, 0, "'Game <id>'"))?
+            .1;
+        let minimum_bag = listing
+            .split(';')
+            .map(|hand| Bag::parse(line, text, hand))
+            .reduce(|min_bag, bag| Ok(min_bag?.grow_to(&bag?)))
+            .expect("split yields at least one hand")?;
+        Ok(Self {
+            id: parse_token(line, text, id, "game id")?,
+            minimum_bag,
+        })
     }
 
     fn possible_by(&self, bag: &Bag) -> bool {
@@ -62,23 +82,73 @@ impl Game {
     }
 }
 
-fn run(input: PuzzleInput, part2: bool) -> usize {
-    let games = input
-        .filter(|line| !line.is_empty())
-        .map(|line| Game::new(&line));
+/// Cube counts of each color parameter
+fn bag(params: &Params) -> Vec<(&'static str, usize)> {
+    Day2::PARAMS
+        .iter()
+        .map(|param| (param.name, params.get(param.name)))
+        .collect()
+}
+
+/// Sum of the IDs of the games which are possible with the cube counts of `bag`
+pub fn possible_id_sum(games: &[Game], bag: &[(&str, usize)]) -> usize {
+    let bag = Bag(bag
+        .iter()
+        .map(|&(cube_type, count)| (cube_type.to_string(), count))
+        .collect());
+    games
+        .iter()
+        .filter(|game| game.possible_by(&bag))
+        .map(|game| game.id)
+        .sum()
+}
+
+fn run(games: &[Game], part2: bool) -> usize {
     if part2 {
-        games.map(|g| g.minimum_bag.power()).sum()
+        games.iter().map(|g| g.minimum_bag.power()).sum()
     } else {
-        let bag = Bag::new("12 red, 13 green, 14 blue");
-        games
-            .filter(|game| game.possible_by(&bag))
-            .map(|game| game.id)
-            .sum()
+        possible_id_sum(games, &bag(&Params::defaults(Day2::PARAMS, 1)))
     }
 }
 
-pub fn solution(input: PuzzleInput, part2: bool) -> String {
-    run(input, part2).to_string()
+/// Sum of the IDs of the games which are possible with 12 red, 13 green and 14 blue cubes
+pub fn part1(games: &[Game]) -> usize {
+    run(games, false)
+}
+
+/// Sum of the powers of the fewest cubes which make each game possible
+pub fn part2(games: &[Game]) -> usize {
+    run(games, true)
+}
+
+pub struct Day2;
+
+impl Puzzle for Day2 {
+    type Input = Vec<Game>;
+
+    const TITLE: &'static str = "Cube Conundrum";
+
+    const PARAMS: &'static [Param] = &[
+        Param::new::<usize>("red", "red cubes in the bag of part 1", "12"),
+        Param::new::<usize>("green", "green cubes in the bag of part 1", "13"),
+        Param::new::<usize>("blue", "blue cubes in the bag of part 1", "14"),
+    ];
+
+    fn parse(input: PuzzleInput) -> Result<Self::Input, ParseError> {
+        input
+            .enumerate()
+            .filter(|(_, text)| !text.is_empty())
+            .map(|(line, text)| Game::parse(line, &text))
+            .collect()
+    }
+
+    fn part1(games: &Self::Input, params: &Params) -> Answer {
+        possible_id_sum(games, &bag(params)).into()
+    }
+
+    fn part2(games: &Self::Input, _params: &Params) -> Answer {
+        part2(games).into()
+    }
 }
 
 #[cfg(test)]
@@ -92,7 +162,20 @@ Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
 Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
 Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
 Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green";
-        assert_eq!(run(test_input.into(), false), 8);
-        assert_eq!(run(test_input.into(), true), 2286);
+        let games = Day2::parse(test_input.into()).unwrap();
+        assert_eq!(run(&games, false), 8);
+        assert_eq!(run(&games, true), 2286);
+
+        let overrides = ["red=20".parse().unwrap(), "blue=15".parse().unwrap()];
+        let params = Params::new(Day2::PARAMS, 1, &overrides).unwrap();
+        assert_eq!(Day2::part1(&games, &params), Answer::from(15_usize));
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let err = Day2::parse("Game 1: 3 blue\nGame 2: x red".into())
+            .err()
+            .unwrap();
+        assert_eq!(err, ParseError::new(1, 8, "cube count"));
     }
 }
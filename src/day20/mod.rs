@@ -1,9 +1,19 @@
-use std::collections::{HashMap, VecDeque};
+//! Day 20: Pulse Propagation
+//!
+//! The [`ModuleNetwork`] holds the wiring of the flip-flop and conjunction modules.
+
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    ops::Range,
+};
 
 use bitvec::prelude::*;
 use itertools::Itertools;
 
-use crate::PuzzleInput;
+use crate::{
+    first_common_step, Answer, Assumption, Cycle, CycleHistory, Param, Params, ParseError, Puzzle,
+    PuzzleInput, Recurrence,
+};
 
 #[derive(Debug, Clone, Copy)]
 enum Signal {
@@ -11,7 +21,7 @@ enum Signal {
     High,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 struct ModuleNetworkState {
     memory: BitVec,
 }
@@ -55,7 +65,7 @@ impl ModuleNetworkState {
         broadcast_ids: &[usize],
         module_outputs: &'a [Vec<Option<usize>>],
         module_memory: &'a Vec<(usize, Option<HashMap<usize, usize>>)>,
-    ) -> NetworkSignalIterator<'_> {
+    ) -> NetworkSignalIterator<'a> {
         let signal_queue: VecDeque<(Signal, usize, Option<usize>)> = broadcast_ids
             .iter()
             .map(|b| (Signal::Low, usize::MAX, Some(*b)))
@@ -115,172 +125,443 @@ enum ModuleSpec {
     Conjunction,
 }
 
-fn run(input: PuzzleInput, part2: bool) -> usize {
-    let modules: HashMap<String, (Option<ModuleSpec>, Vec<String>)> = input
-        .map(|line| {
-            let (label, output_nodes) = line.splitn(2, " -> ").collect_tuple().unwrap();
-            let outputs = output_nodes
-                .split(", ")
-                .filter(|s| !s.is_empty())
-                .map(|s| s.to_string())
-                .collect_vec();
-            if label == "broadcaster" {
-                (label.to_string(), (None, outputs))
-            } else {
-                (
-                    label[1..].to_string(),
+/// Module wiring by module index (the broadcaster is not a module)
+pub struct ModuleNetwork {
+    broadcast_ids: Vec<usize>,
+    module_outputs: Vec<Vec<Option<usize>>>,
+    module_inputs: Vec<Vec<usize>>,
+    module_memory: Vec<(usize, Option<HashMap<usize, usize>>)>,
+    /// Modules which send to `rx`
+    rx_senders: Vec<usize>,
+}
+
+impl ModuleNetwork {
+    fn parse(input: PuzzleInput) -> Result<Self, ParseError> {
+        let mut broadcaster_line = None;
+        let modules: HashMap<String, (Option<ModuleSpec>, Vec<String>)> = input
+            .enumerate()
+            .filter(|(_, text)| !text.is_empty())
+            .map(|(line, text)| {
+                let (label, output_nodes) = text
+                    .split_once(" -> ")
+                    .ok_or_else(|| ParseError::at_end(line, &text, "' -> ' after module"))?;
+                let outputs = output_nodes
+                    .split(", ")
+                    .filter(|s| !s.is_empty())
+                    .map(|s| s.to_string())
+                    .collect_vec();
+                if label == "broadcaster" {
+                    broadcaster_line = Some(line);
+                    Ok((label.to_string(), (None, outputs)))
+                } else {
+                    let spec = match label.chars().next() {
+                        Some('%') => ModuleSpec::FlipFlop,
+                        Some('&') => ModuleSpec::Conjunction,
+                        _ => {
+                            return Err(ParseError::new(
+                                line,
+                                0,
+                                "'%' or '&' module prefix or 'broadcaster'",
+                            ))
+                        }
+                    };
+                    Ok((label[1..].to_string(), (Some(spec), outputs)))
+                }
+            })
+            .try_collect()?;
+
+        // Note that broadcaster only has flipflop outputs
+        let module_indices: HashMap<String, usize> = modules
+            .iter()
+            // Discard broadcaster module
+            .filter(|(_, (m, _))| m.is_some())
+            .enumerate()
+            .map(|(i, (label, _))| (label.clone(), i))
+            .collect();
+
+        let broadcaster_line =
+            broadcaster_line.ok_or_else(|| ParseError::new(0, 0, "'broadcaster' module"))?;
+        let broadcast_ids = modules["broadcaster"]
+            .1
+            .iter()
+            .map(|o| {
+                module_indices.get(o).copied().ok_or_else(|| {
+                    ParseError::new(broadcaster_line, 0, format!("module '{o}' to exist"))
+                })
+            })
+            .try_collect()?;
+
+        let (module_specs, module_outputs): (Vec<ModuleSpec>, Vec<Vec<Option<usize>>>) =
+            module_indices
+                .iter()
+                .sorted_by_key(|&(_, index)| index)
+                .map(|(label, _)| {
+                    let (spec, outputs) = &modules[label];
                     (
-                        Some(if label.starts_with('%') {
-                            ModuleSpec::FlipFlop
-                        } else {
-                            ModuleSpec::Conjunction
-                        }),
-                        outputs,
-                    ),
-                )
+                        spec.to_owned().unwrap(),
+                        outputs
+                            .iter()
+                            .map(|l| module_indices.get(l).copied())
+                            .collect_vec(),
+                    )
+                })
+                .unzip();
+
+        let rx_senders = module_indices
+            .iter()
+            .filter(|(label, _)| modules[*label].1.iter().any(|o| o == "rx"))
+            .map(|(_, index)| *index)
+            .sorted()
+            .collect_vec();
+
+        let module_inputs = {
+            let mut module_inputs: Vec<Vec<usize>> =
+                module_outputs.iter().map(|_| vec![]).collect_vec();
+            for (index, outputs) in module_outputs.iter().enumerate() {
+                for output in outputs.iter().flatten() {
+                    module_inputs[*output].push(index);
+                }
             }
+            module_inputs
+        };
+        let module_memory = module_specs
+            .iter()
+            .zip_eq(&module_inputs)
+            .scan(0, |memory_offset, (m, inputs)| {
+                Some(match m {
+                    ModuleSpec::Conjunction => {
+                        let id_to_index: HashMap<usize, usize> = inputs
+                            .iter()
+                            .enumerate()
+                            .map(|(index, id)| (*id, index))
+                            .collect();
+                        let offset = *memory_offset;
+                        *memory_offset += inputs.len();
+                        (offset, Some(id_to_index))
+                    }
+                    ModuleSpec::FlipFlop => {
+                        let offset = *memory_offset;
+                        *memory_offset += 1;
+                        (offset, None)
+                    }
+                })
+            })
+            .collect_vec();
+
+        Ok(Self {
+            broadcast_ids,
+            module_outputs,
+            module_inputs,
+            module_memory,
+            rx_senders,
         })
-        .collect();
-
-    // Note that broadcaster only has flipflop outputs
-    let module_indices: HashMap<String, usize> = modules
-        .iter()
-        // Discard broadcaster module
-        .filter(|(_, (m, _))| m.is_some())
-        .enumerate()
-        .map(|(i, (label, _))| (label.clone(), i))
-        .collect();
-
-    let broadcast_ids = modules["broadcaster"]
-        .1
-        .iter()
-        .map(|o| module_indices[o])
-        .collect_vec();
-
-    let (module_specs, module_outputs): (Vec<ModuleSpec>, Vec<Vec<Option<usize>>>) = module_indices
-        .iter()
-        .sorted_by_key(|&(_, index)| index)
-        .map(|(label, _)| {
-            let (spec, outputs) = &modules[label];
-            (
-                spec.to_owned().unwrap(),
-                outputs
-                    .iter()
-                    .map(|l| module_indices.get(l).copied())
-                    .collect_vec(),
-            )
-        })
-        .unzip();
-
-    let module_inputs = {
-        let mut module_inputs: Vec<Vec<usize>> =
-            module_outputs.iter().map(|_| vec![]).collect_vec();
-        for (index, outputs) in module_outputs.iter().enumerate() {
-            for output in outputs.iter().flatten() {
-                module_inputs[*output].push(index);
+    }
+
+    /// Memory bits of a module: One per input for conjunctions, one for flipflops
+    fn memory_range(&self, module: usize) -> Range<usize> {
+        let (offset, id_to_index_opt) = &self.module_memory[module];
+        let len = id_to_index_opt
+            .as_ref()
+            .map_or(1, |id_to_index| id_to_index.len());
+        *offset..offset + len
+    }
+
+    fn new_state(&self) -> ModuleNetworkState {
+        ModuleNetworkState::new(
+            (0..self.module_memory.len())
+                .map(|m| self.memory_range(m).len())
+                .sum(),
+        )
+    }
+
+    /// Modules which `module` depends on, including itself
+    fn upstream_modules(&self, module: usize) -> HashSet<usize> {
+        let mut upstream = HashSet::from([module]);
+        let mut queue = vec![module];
+        while let Some(m) = queue.pop() {
+            for &input in &self.module_inputs[m] {
+                if upstream.insert(input) {
+                    queue.push(input);
+                }
             }
         }
-        module_inputs
-    };
-    let module_memory = module_specs
-        .iter()
-        .zip_eq(&module_inputs)
-        .scan(0, |memory_offset, (m, inputs)| {
-            Some(match m {
-                ModuleSpec::Conjunction => {
-                    let id_to_index: HashMap<usize, usize> = inputs
-                        .iter()
-                        .enumerate()
-                        .map(|(index, id)| (*id, index))
-                        .collect();
-                    let offset = *memory_offset;
-                    *memory_offset += inputs.len();
-                    (offset, Some(id_to_index))
-                }
-                ModuleSpec::FlipFlop => {
-                    let offset = *memory_offset;
-                    *memory_offset += 1;
-                    (offset, None)
-                }
+        upstream
+    }
+
+    /// Press the button and check whether `rx` receives a LOW signal
+    fn press_sends_rx_low(&self, state: &mut ModuleNetworkState) -> bool {
+        // A sender sends the same signal to all its outputs, so a LOW signal
+        // to any untyped module is also sent to `rx`
+        self.press_button(state)
+            .any(|(signal, sender_id, receiver_id_opt)| {
+                matches!(signal, Signal::Low)
+                    && receiver_id_opt.is_none()
+                    && self.rx_senders.contains(&sender_id)
             })
+    }
+
+    fn press_button<'a>(&'a self, state: &'a mut ModuleNetworkState) -> NetworkSignalIterator<'a> {
+        state.iterate_button_press(
+            &self.broadcast_ids,
+            &self.module_outputs,
+            &self.module_memory,
+        )
+    }
+}
+
+/// Most presses which are simulated to find the first LOW signal to `rx`
+const MAX_PRESSES: usize = 1 << 20;
+
+/// Conjunction which sends to `rx` and the disjoint module sets driving each of its inputs
+struct Counters {
+    conjunction: usize,
+    inputs: Vec<(usize, HashSet<usize>)>,
+}
+
+impl Counters {
+    /// Split the network before `rx` into independent counters, if it decomposes
+    fn find(network: &ModuleNetwork) -> Option<Self> {
+        let &[conjunction] = &network.rx_senders[..] else {
+            return None;
+        };
+        network.module_memory[conjunction].1.as_ref()?;
+        let inputs = network.module_inputs[conjunction]
+            .iter()
+            .map(|&input| (input, network.upstream_modules(input)))
+            .collect_vec();
+        let independent = inputs.iter().tuple_combinations().all(|((_, a), (_, b))| {
+            a.is_disjoint(b) && !a.contains(&conjunction) && !b.contains(&conjunction)
+        });
+        independent.then_some(Self {
+            conjunction,
+            inputs,
         })
-        .collect_vec();
+    }
 
-    let mut network_state = ModuleNetworkState::new(
-        module_memory
+    /// Cycles of the counters until each counter state (its module memory
+    /// and its conjunction input) repeats.
+    /// Returns `None` if a counter does not repeat within `max_presses`.
+    fn cycles(&self, network: &ModuleNetwork, max_presses: usize) -> Option<CounterCycles> {
+        let (conjunction_offset, id_to_index_opt) = &network.module_memory[self.conjunction];
+        let id_to_index = id_to_index_opt.as_ref().unwrap();
+        let counter_bits = self
+            .inputs
             .iter()
-            .map(|(_, v)| {
-                if let Some(id_to_index) = v {
-                    id_to_index.len()
-                } else {
-                    1
-                }
+            .map(|(input, modules)| {
+                let input_bit = conjunction_offset + id_to_index[input];
+                let module_bits = modules
+                    .iter()
+                    .sorted()
+                    .flat_map(|&m| network.memory_range(m));
+                (input_bit, module_bits.chain([input_bit]).collect_vec())
             })
-            .sum(),
-    );
-
-    if part2 {
-        // The input nework has a specific structure with flipflop cycles
-        // and conjunctions before the `rx` target which wait for these cycles to align.
-        // All cycles start with the first cycle.
-        let last_conjunction = module_outputs
-            .iter()
-            .position(|m| m.iter().all(|output| output.is_none()))
-            .unwrap();
-        // If all cycle output conjunctions send HIGH signals
-        // to the last conjunction, `rx` receives low.
-        let cycle_output_conjunctions = &module_inputs[last_conjunction].clone();
-
-        // Record cycle numbers where conjunctions emit a low signal
-        let mut conjunction_low_signal: Vec<Option<usize>> =
-            cycle_output_conjunctions.iter().map(|_| None).collect();
-        let mut cycle = 0;
-        while conjunction_low_signal.iter().any(|h| h.is_none()) {
-            for (signal, sender_id, receiver_id_opt) in
-                network_state.iterate_button_press(&broadcast_ids, &module_outputs, &module_memory)
-            {
-                if receiver_id_opt.is_some_and(|r| r == last_conjunction)
-                    && matches!(signal, Signal::High)
-                {
-                    if let Some(cycle_low) = cycle_output_conjunctions
+            .collect_vec();
+
+        let mut network_state = network.new_state();
+        let mut histories: Vec<CycleHistory<BitVec>> =
+            self.inputs.iter().map(|_| CycleHistory::new()).collect();
+        let mut high_presses: Vec<Vec<u64>> = vec![vec![]; self.inputs.len()];
+        let mut cycles: Vec<Option<(Cycle, Recurrence)>> = vec![None; self.inputs.len()];
+        for press in 0..max_presses {
+            let mut high = vec![false; self.inputs.len()];
+            for (counter, (input_bit, bits)) in counter_bits.iter().enumerate() {
+                if cycles[counter].is_some() {
+                    continue;
+                }
+                let counter_state = bits.iter().map(|&b| network_state.memory[b]).collect();
+                if let Some(cycle) = histories[counter].record(counter_state) {
+                    let recurrence = Recurrence::new(
+                        std::mem::take(&mut high_presses[counter]),
+                        cycle.prefix as u64,
+                        cycle.period as u64,
+                    );
+                    cycles[counter] = Some((cycle, recurrence));
+                }
+                high[counter] = network_state.memory[*input_bit];
+            }
+            if cycles.iter().all(|c| c.is_some()) {
+                let (cycles, high_presses) = cycles.into_iter().flatten().unzip();
+                return Some(CounterCycles {
+                    bits: counter_bits.into_iter().map(|(_, bits)| bits).collect(),
+                    histories,
+                    cycles,
+                    high_presses,
+                });
+            }
+            for (signal, sender_id, receiver_id_opt) in network.press_button(&mut network_state) {
+                if receiver_id_opt == Some(self.conjunction) && matches!(signal, Signal::High) {
+                    let counter = self
+                        .inputs
                         .iter()
-                        .position(|c| *c == sender_id)
-                    {
-                        conjunction_low_signal[cycle_low] = Some(cycle);
-                    }
+                        .position(|(input, _)| *input == sender_id);
+                    high[counter.unwrap()] = true;
                 }
             }
-            cycle += 1;
+            for (counter, _) in high.iter().enumerate().filter(|(_, high)| **high) {
+                high_presses[counter].push(press as u64);
+            }
         }
-        conjunction_low_signal
-            .into_iter()
-            .flatten()
-            // A cycle restarts one cycle after the low signal is send
-            .map(|low_singal_cycle| low_singal_cycle + 1)
-            // All cycle lengths are prime => product gives cycle when all align
-            .product()
-    } else {
-        let mut total_low = 0;
-        let mut total_high = 0;
-
-        for _ in 0..1000 {
-            // One low for button press
-            total_low += 1;
-            for (signal, _, _) in
-                network_state.iterate_button_press(&broadcast_ids, &module_outputs, &module_memory)
-            {
-                match signal {
-                    Signal::Low => total_low += 1,
-                    Signal::High => total_high += 1,
-                }
+        None
+    }
+
+    /// Presses until `rx` receives a LOW signal.
+    /// The conjunction only sends LOW on presses when all counters hold HIGH,
+    /// and such a press is confirmed by simulating it from the combined counter states,
+    /// since the counters need to hold HIGH at the same moment within the press.
+    /// Returns `None` if a counter does not repeat within `max_presses`
+    /// or no press is confirmed within `max_presses` candidates.
+    fn presses_until_rx_low(&self, network: &ModuleNetwork, max_presses: usize) -> Option<usize> {
+        let cycles = self.cycles(network, max_presses)?;
+        let mut candidate_start = 0;
+        for _ in 0..max_presses {
+            let candidate = cycles.first_all_high_press(candidate_start)?;
+            if cycles.sends_rx_low(network, candidate) {
+                return Some(candidate + 1);
+            }
+            candidate_start = candidate + 1;
+        }
+        None
+    }
+}
+
+/// Repeating states of independent counters
+struct CounterCycles {
+    /// Memory bits of each counter, including its conjunction input
+    bits: Vec<Vec<usize>>,
+    histories: Vec<CycleHistory<BitVec>>,
+    cycles: Vec<Cycle>,
+    /// Presses (from 0) on which each counter holds HIGH at the conjunction at some moment
+    high_presses: Vec<Recurrence>,
+}
+
+impl CounterCycles {
+    /// First press from `start` on which all counters hold HIGH at the conjunction
+    fn first_all_high_press(&self, start: usize) -> Option<usize> {
+        first_common_step(&self.high_presses, start as u64).map(|press| press as usize)
+    }
+
+    /// Simulate `press` from the combined counter states before it
+    /// and check whether `rx` receives a LOW signal
+    fn sends_rx_low(&self, network: &ModuleNetwork, press: usize) -> bool {
+        let mut network_state = network.new_state();
+        for ((cycle, history), bits) in self.cycles.iter().zip(&self.histories).zip(&self.bits) {
+            let counter_state = history.get(cycle.reduce(press)).unwrap();
+            for (&b, bit) in bits.iter().zip(counter_state) {
+                network_state.memory.set(b, *bit);
+            }
+        }
+        network.press_sends_rx_low(&mut network_state)
+    }
+}
+
+/// Presses until `rx` receives a LOW signal by simulating each press.
+/// Returns `None` if the network state repeats before or `max_presses` are exceeded.
+fn simulate_until_rx_low(network: &ModuleNetwork, max_presses: usize) -> Option<usize> {
+    let mut network_state = network.new_state();
+    let mut history = CycleHistory::new();
+    for press in 1..=max_presses {
+        if history.record(network_state.clone()).is_some() {
+            return None;
+        }
+        if network.press_sends_rx_low(&mut network_state) {
+            return Some(press);
+        }
+    }
+    None
+}
+
+/// Fewest button presses after which `rx` receives a LOW signal
+fn presses_until_rx_low(network: &ModuleNetwork, max_presses: usize) -> Option<usize> {
+    match Counters::find(network) {
+        Some(counters) => counters.presses_until_rx_low(network, max_presses),
+        None => simulate_until_rx_low(network, max_presses),
+    }
+}
+
+/// Product of the numbers of low and high pulses sent during `presses` button presses
+pub fn pulse_product(network: &ModuleNetwork, presses: usize) -> usize {
+    let mut network_state = network.new_state();
+    let mut total_low = 0;
+    let mut total_high = 0;
+
+    for _ in 0..presses {
+        // One low for button press
+        total_low += 1;
+        for (signal, _, _) in network.press_button(&mut network_state) {
+            match signal {
+                Signal::Low => total_low += 1,
+                Signal::High => total_high += 1,
             }
         }
-        total_low * total_high
     }
+    total_low * total_high
+}
+
+/// Product of the numbers of low and high pulses sent during 1000 button presses
+pub fn part1(network: &ModuleNetwork) -> usize {
+    pulse_product(network, Params::defaults(Day20::PARAMS, 1).get("presses"))
 }
 
-pub fn solution(input: PuzzleInput, part2: bool) -> String {
-    run(input, part2).to_string()
+/// Fewest button presses until `rx` receives a low pulse, if it does within the simulated presses.
+/// The input network usually has independent flipflop counters
+/// before the conjunction sending to `rx`, which wait for these counters to align.
+pub fn part2(network: &ModuleNetwork) -> Option<usize> {
+    presses_until_rx_low(network, MAX_PRESSES)
+}
+
+pub struct Day20;
+
+impl Puzzle for Day20 {
+    type Input = ModuleNetwork;
+
+    const TITLE: &'static str = "Pulse Propagation";
+
+    const PARAMS: &'static [Param] = &[Param::new::<usize>(
+        "presses",
+        "button presses of part 1",
+        "1000",
+    )];
+
+    fn parse(input: PuzzleInput) -> Result<Self::Input, ParseError> {
+        ModuleNetwork::parse(input)
+    }
+
+    fn part1(network: &Self::Input, params: &Params) -> Answer {
+        pulse_product(network, params.get("presses")).into()
+    }
+
+    fn part2(network: &Self::Input, _params: &Params) -> Answer {
+        part2(network).map_or_else(
+            || Answer::Unsolved("rx does not receive a LOW signal".into()),
+            Answer::from,
+        )
+    }
+
+    fn assumptions(network: &Self::Input, part2: bool, _params: &Params) -> Vec<Assumption> {
+        if !part2 {
+            return vec![];
+        }
+        let counters = Counters::find(network);
+        let cycles = counters
+            .as_ref()
+            .and_then(|counters| counters.cycles(network, MAX_PRESSES));
+        vec![
+            Assumption::new(
+                "a single conjunction with independent counters as inputs sends to `rx` \
+                 (otherwise presses are simulated one by one)",
+                counters.is_some(),
+            ),
+            Assumption::new(
+                "on the first press all counters hold HIGH at the conjunction, \
+                 they hold HIGH at the same moment (otherwise later presses are checked)",
+                cycles.is_some_and(|cycles| {
+                    cycles
+                        .first_all_high_press(0)
+                        .is_some_and(|press| cycles.sends_rx_low(network, press))
+                }),
+            ),
+        ]
+    }
 }
 
 #[cfg(test)]
@@ -294,13 +575,121 @@ mod test {
 %b -> c
 %c -> inv
 &inv -> a";
-        assert_eq!(run(test_input1.into(), false), 32000000);
+        assert_eq!(
+            part1(&ModuleNetwork::parse(test_input1.into()).unwrap()),
+            32000000
+        );
         let test_input2 = r"broadcaster -> a
 %a -> inv, con
 &inv -> b
 %b -> con
 &con -> output";
-        assert_eq!(run(test_input2.into(), false), 11687500);
-        //assert_eq!(run(test_input.into(), true), 0);
+        assert_eq!(
+            part1(&ModuleNetwork::parse(test_input2.into()).unwrap()),
+            11687500
+        );
+        // One press sends 8 low and 4 high pulses
+        let network1 = ModuleNetwork::parse(test_input1.into()).unwrap();
+        assert_eq!(pulse_product(&network1, 1), 32);
+    }
+
+    #[test]
+    fn test_presses_until_rx_low() {
+        let presses = |input: &'static str| {
+            let network = ModuleNetwork::parse(input.into()).unwrap();
+            let simulated = simulate_until_rx_low(&network, 100);
+            let counted = Counters::find(&network).map(|c| c.presses_until_rx_low(&network, 100));
+            (counted, simulated)
+        };
+        // Binary counters which send HIGH to `con` on presses 3, 7, ... and 2, 3, 6, 7, ...
+        let counters = r"broadcaster -> a0, b0
+%a0 -> a1
+%a1 -> ia
+&ia -> ta
+&ta -> con
+%b0 -> b1, ib
+%b1 -> ib
+&ib -> tb
+&tb -> con
+&con -> rx";
+        assert_eq!(presses(counters), (Some(Some(3)), Some(3)));
+        // Counters with periods 4 and 8 which are not coprime
+        let counters = r"broadcaster -> a0, b0
+%a0 -> a1, ia
+%a1 -> ia
+&ia -> ta
+&ta -> con
+%b0 -> b1
+%b1 -> b2, ib
+%b2 -> ib
+&ib -> tb
+&tb -> con
+&con -> rx";
+        assert_eq!(presses(counters), (Some(Some(7)), Some(7)));
+        // Both counters send HIGH to `con` on press 3, but not at the same moment
+        let counters = r"broadcaster -> m0x0, m1x0
+%m0x0 -> m0x1, m0x2
+%m0x1 -> m0x2
+&m0x2 -> t0
+&t0 -> con
+%m1x0 -> t1, m1x1
+%m1x1 -> t1
+&t1 -> con
+&con -> rx";
+        assert_eq!(presses(counters), (Some(Some(4)), Some(4)));
+        // Flipflop `s` drives both counters
+        let shared = r"broadcaster -> s
+%s -> a0, b0
+%a0 -> ia
+&ia -> ta
+&ta -> con
+%b0 -> b1
+%b1 -> ib
+&ib -> tb
+&tb -> con
+&con -> rx";
+        assert_eq!(presses(shared), (None, Some(6)));
+        assert_eq!(
+            part2(&ModuleNetwork::parse(shared.into()).unwrap()),
+            Some(6)
+        );
+        // Network state repeats without `rx` receiving LOW
+        assert_eq!(presses("broadcaster -> a\n%a -> b\n%b -> a"), (None, None));
+    }
+
+    #[test]
+    fn test_assumptions() {
+        let holds = |input: &'static str| {
+            let network = ModuleNetwork::parse(input.into()).unwrap();
+            Day20::assumptions(&network, true, &Params::defaults(Day20::PARAMS, 2))[0].holds
+        };
+        assert!(!holds("broadcaster -> a\n%a -> b\n%b -> a"));
+        assert!(holds(
+            "broadcaster -> a, b\n%a -> con\n%b -> con\n&con -> rx"
+        ));
+        // Inputs of `con` share flipflop `a`
+        assert!(!holds(
+            "broadcaster -> a\n%a -> b, con\n%b -> con\n&con -> rx"
+        ));
+
+        let aligned = |input: &'static str| {
+            let network = ModuleNetwork::parse(input.into()).unwrap();
+            Day20::assumptions(&network, true, &Params::defaults(Day20::PARAMS, 2))[1].holds
+        };
+        assert!(aligned(
+            "broadcaster -> a, b\n%a -> con\n%b -> con\n&con -> rx"
+        ));
+        // Both counters send HIGH to `con` on press 3, but not at the same moment
+        assert!(!aligned(
+            "broadcaster -> m0x0, m1x0
+%m0x0 -> m0x1, m0x2
+%m0x1 -> m0x2
+&m0x2 -> t0
+&t0 -> con
+%m1x0 -> t1, m1x1
+%m1x1 -> t1
+&t1 -> con
+&con -> rx"
+        ));
     }
 }
@@ -1,152 +1,178 @@
-use std::collections::{HashSet, VecDeque};
+//! Day 21: Step Counter
+//!
+//! The [`Garden`] holds the rocks and the start tile.
 
-use num_integer::{Integer, Roots};
+use std::collections::HashSet;
 
-use crate::PuzzleInput;
+use num_integer::Integer;
 
-type Position = usize;
-#[derive(Debug)]
-enum Direction {
-    Right,
-    Up,
-    Left,
-    Down,
-}
-const DIRS: [Direction; 4] = [
-    Direction::Right,
-    Direction::Up,
-    Direction::Left,
-    Direction::Down,
-];
-
-struct Garden {
-    rocks: Vec<bool>,
-    size: usize,
+use crate::{
+    bfs_layers, Answer, Assumption, BitGrid, Direction, Grid, GridShape, Param, Params, ParseError,
+    Point, Position, Puzzle, PuzzleInput,
+};
+
+pub struct Garden {
+    rocks: BitGrid,
+    start: Position,
 }
 
 impl Garden {
-    fn parse(lines: impl Iterator<Item = String>) -> Self {
-        let mut rocks: Vec<bool> = vec![];
-
-        for line in lines {
-            for c in line.chars() {
-                rocks.push(c == '#');
+    fn parse(lines: impl Iterator<Item = String>) -> Result<Self, ParseError> {
+        let mut start = None;
+        let mut pos = 0;
+        let tiles = Grid::parse(lines.enumerate(), ".#S", |c| {
+            if c == 'S' {
+                start.get_or_insert(pos);
             }
-        }
-        Self {
-            size: rocks.len().sqrt(),
-            rocks,
-        }
+            pos += 1;
+            c
+        })?;
+        Ok(Self {
+            rocks: tiles.to_bits(|&c| c == '#'),
+            start: start.ok_or_else(|| ParseError::new(0, 0, "'S' start tile"))?,
+        })
     }
 
-    fn step(&self, position: Position, direciton: Direction) -> Option<Position> {
-        let (row, col) = position.div_rem(&self.size);
-        if match direciton {
-            Direction::Right => col < self.size - 1,
-            Direction::Up => row > 0,
-            Direction::Left => col > 0,
-            Direction::Down => row < self.size - 1,
-        } {
-            let next_pos = match direciton {
-                Direction::Right => position + 1,
-                Direction::Up => position - self.size,
-                Direction::Left => position - 1,
-                Direction::Down => position + self.size,
-            };
-            if !self.rocks[next_pos] {
-                Some(next_pos)
-            } else {
-                None
-            }
-        } else {
-            None
-        }
+    fn step(&self, position: Position, direction: Direction) -> Option<Position> {
+        self.rocks
+            .step(position, direction)
+            .filter(|&next_pos| !self.rocks.get(next_pos))
     }
 
     fn center(&self) -> Position {
-        let c = self.size / 2;
-        c * self.size + c
+        self.rocks
+            .position(self.rocks.height() / 2, self.rocks.width() / 2)
+    }
+
+    fn is_odd_square(&self) -> bool {
+        let (width, height) = (self.rocks.width(), self.rocks.height());
+        width == height && width % 2 == 1
+    }
+
+    fn has_clear_lines(&self) -> bool {
+        let (row, col) = self.rocks.row_col(self.start);
+        self.rocks.row(row).not_any()
+            && (0..self.rocks.height()).all(|r| !self.rocks.get(self.rocks.position(r, col)))
+    }
+
+    /// Whether `steps` end at the border of a garden beyond the starting one
+    fn ends_at_border(&self, steps: usize) -> bool {
+        let size = self.rocks.width();
+        size > 0
+            && steps
+                .checked_sub(size / 2)
+                .is_some_and(|s| s >= size && s.is_multiple_of(size))
+    }
+
+    /// Whether the fast diamond extrapolation of `count_diamond` applies to `steps`
+    fn fits_diamond(&self, steps: usize) -> bool {
+        self.is_odd_square()
+            && self.start == self.center()
+            && self.has_clear_lines()
+            && self.ends_at_border(steps)
+    }
+
+    /// Input properties which the fast diamond extrapolation of `steps` relies on
+    /// (otherwise the plots are counted by the slower `count_reachable_plots_endless`)
+    fn assumptions(&self, steps: usize) -> Vec<Assumption> {
+        vec![
+            Assumption::new(
+                "garden is square with an odd side length",
+                self.is_odd_square(),
+            ),
+            Assumption::new("start is at the center", self.start == self.center()),
+            Assumption::new(
+                "row and column of the start are free of rocks",
+                self.has_clear_lines(),
+            ),
+            Assumption::new(
+                "steps end at a garden border, so that no partial gardens remain",
+                self.ends_at_border(steps),
+            ),
+        ]
+    }
+
+    /// Number of plots first reached after each step in the endless garden
+    fn endless_layers(&self) -> impl Iterator<Item = usize> + '_ {
+        let (width, height) = (self.rocks.width() as isize, self.rocks.height() as isize);
+        let is_plot = move |point: &Point| {
+            let tile = Point::new(point.row.mod_floor(&height), point.col.mod_floor(&width));
+            !self.rocks.get(self.rocks.position_of(tile).unwrap())
+        };
+        // Steps never skip a layer, so the two latest layers are enough to avoid revisits
+        let mut previous: HashSet<Point> = HashSet::new();
+        let mut current: HashSet<Point> = [self.rocks.point(self.start)].into_iter().collect();
+        std::iter::from_fn(move || {
+            let count = current.len();
+            let next = current
+                .iter()
+                .flat_map(|&point| Direction::ALL.map(|d| point + d.vector()))
+                .filter(|next| is_plot(next) && !previous.contains(next) && !current.contains(next))
+                .collect();
+            previous = std::mem::replace(&mut current, next);
+            Some(count)
+        })
     }
 
-    #[allow(unused)]
-    /// Inefficient endless garden plot count for testing
+    /// Endless garden plot count for any start and number of steps.
+    /// Once the reached plots spread over many gardens, each layer grows by a fixed amount
+    /// per period of steps, which extrapolates the layers beyond the simulated steps.
+    /// (This settles quickly if the garden border is free of rocks, otherwise it may take
+    /// simulating all `steps`.)
     fn count_reachable_plots_endless(&self, steps: usize) -> usize {
+        let (width, height) = (self.rocks.width(), self.rocks.height());
+        // Even, so that each extrapolated layer keeps its checkerboard parity
+        let period = 2 * width.lcm(&height);
+        let min_layers = 2 * (width + height) + 3 * period;
         // Reachable plots are in a checkerboard pattern
-        let even = steps % 2;
-        let (row, col) = {
-            let (r, c) = self.center().div_rem(&self.size);
-            (r as isize, c as isize)
-        };
+        let parity_sum =
+            |layers: &[usize]| -> usize { layers.iter().skip(steps % 2).step_by(2).sum() };
 
-        let mut positions: VecDeque<((isize, isize), usize)> = [((0, 0), 0)].into_iter().collect();
-        let mut visited: HashSet<(isize, isize)> = [(0, 0)].into_iter().collect();
-        let mut plots = 0;
-        while let Some(((y, x), step)) = positions.pop_front() {
-            if step % 2 == even {
-                plots += 1;
+        let mut layers = vec![];
+        for layer in self.endless_layers() {
+            layers.push(layer);
+            let last = layers.len() - 1;
+            if last == steps {
+                return parity_sum(&layers);
             }
-
-            if step < steps {
-                positions.extend(
-                    DIRS.into_iter()
-                        .filter_map(|d| {
-                            let (yn, xn) = match d {
-                                Direction::Right => (y, x + 1),
-                                Direction::Up => (y - 1, x),
-                                Direction::Left => (y, x - 1),
-                                Direction::Down => (y + 1, x),
-                            };
-                            let s = self.size as isize;
-                            let (r, c) = ((row + yn).mod_floor(&s), (col + xn).mod_floor(&s));
-                            if !self.rocks[(r * s + c) as usize] {
-                                Some((yn, xn))
-                            } else {
-                                None
-                            }
-                        })
-                        .filter(|p| visited.insert(*p))
-                        .map(|p| (p, step + 1)),
-                );
+            let grows_linearly = last >= min_layers
+                && (last + 1 - 2 * period..=last - period)
+                    .all(|s| layers[s + period] + layers[s - period] == 2 * layers[s]);
+            if grows_linearly {
+                break;
             }
         }
-        plots
+
+        let last = layers.len() - 1;
+        let extrapolated: usize = (last + 1 - period..=last)
+            .filter(|s| s % 2 == steps % 2)
+            .map(|s| {
+                let repeats = (steps - s) / period;
+                let growth = layers[s] - layers[s - period];
+                repeats * layers[s] + growth * repeats * (repeats + 1) / 2
+            })
+            .sum();
+        parity_sum(&layers) + extrapolated
     }
 
     fn count_reachable_plots(&self, start: Position, steps: usize) -> usize {
+        let neighbors = |&pos: &Position| {
+            Direction::ALL
+                .into_iter()
+                .filter_map(move |d| self.step(pos, d))
+        };
         // Reachable plots are in a checkerboard pattern
-        let mut plots = 0;
-        let even = steps % 2;
-
-        // Find all reachable positions
-        let mut positions: VecDeque<(Position, usize)> = [(start, 0)].into_iter().collect();
-        let mut visited = [false].repeat(self.rocks.len());
-        visited[start] = true;
-        while let Some((pos, step)) = positions.pop_front() {
-            plots += (step % 2 == even) as usize;
-
-            if step < steps {
-                positions.extend(
-                    DIRS.into_iter()
-                        .filter_map(|d| self.step(pos, d))
-                        .filter(|p| {
-                            let v = &mut visited[*p];
-                            let is_new = !*v;
-                            if is_new {
-                                *v = true;
-                            }
-                            is_new
-                        })
-                        .map(|p| (p, step + 1)),
-                );
-            }
-        }
-
-        plots
+        bfs_layers([start], neighbors)
+            .take(steps.saturating_add(1))
+            .skip(steps % 2)
+            .step_by(2)
+            .map(|layer| layer.len())
+            .sum()
     }
 }
 
 fn quadrant_odd_count(length: usize) -> usize {
-    let l = (length + 1) / 2;
+    let l = length.div_ceil(2);
     l * l
 }
 
@@ -155,89 +181,137 @@ fn quadrant_even_count(length: usize) -> usize {
     l * (l + 1)
 }
 
-fn run(input: PuzzleInput, steps: usize) -> usize {
-    let garden = Garden::parse(input);
-    let start_border_distance = garden.size / 2;
-
+/// Endless garden plot count of a centered start for `steps` which end at a garden border.
+/// Relies on assumptions which are not specified in puzzle text (see `Garden::assumptions`).
+fn count_diamond(garden: &Garden, steps: usize) -> usize {
     let center_start = garden.center();
-    if steps < start_border_distance {
-        garden.count_reachable_plots(center_start, steps)
-    } else {
-        // Assumptions which are not specified in puzzle text:
-        // - Start is at the center of the square garden (with odd side lengths)
-        // - Start has unobstructed vertical and horizontal lines
-        let full_count = garden.count_reachable_plots(center_start, steps);
-        let full_odd_count = garden.count_reachable_plots(center_start, steps - 1);
-        let (garden_repeats, residual_steps) =
-            (steps - start_border_distance).div_rem(&garden.size);
-        assert!(
-            residual_steps == 0,
-            "required to simplify (for garden_repeats-1 to be full & exactly one peak garden)"
-        );
-        let peak_steps = garden.size - 1;
-        let full_gardens = garden_repeats - 1;
-        let quadrant_full_even_gardens = quadrant_even_count(full_gardens);
-        let quadrant_full_odd_gardens = quadrant_odd_count(full_gardens);
-        let quadrant_full_plots = (full_count * quadrant_full_even_gardens)
-            + (full_odd_count * quadrant_full_odd_gardens);
-
-        let quadrant_border_plots = DIRS
-            .into_iter()
-            .map(|d| {
-                let peak_garden_plots = {
-                    let (row, col) = match d {
-                        Direction::Right => (start_border_distance, 0),
-                        Direction::Up => (garden.size - 1, start_border_distance),
-                        Direction::Left => (start_border_distance, garden.size - 1),
-                        Direction::Down => (0, start_border_distance),
-                    };
-                    let peak_start = row * garden.size + col;
-                    garden.count_reachable_plots(peak_start, peak_steps)
+    let size = garden.rocks.width();
+    let start_border_distance = size / 2;
+    let full_count = garden.count_reachable_plots(center_start, steps);
+    let full_odd_count = garden.count_reachable_plots(center_start, steps - 1);
+    let (garden_repeats, residual_steps) = (steps - start_border_distance).div_rem(&size);
+    assert!(
+        residual_steps == 0,
+        "required to simplify (for garden_repeats-1 to be full & exactly one peak garden)"
+    );
+    let peak_steps = size - 1;
+    let full_gardens = garden_repeats - 1;
+    let quadrant_full_even_gardens = quadrant_even_count(full_gardens);
+    let quadrant_full_odd_gardens = quadrant_odd_count(full_gardens);
+    let quadrant_full_plots =
+        (full_count * quadrant_full_even_gardens) + (full_odd_count * quadrant_full_odd_gardens);
+
+    let quadrant_border_plots = Direction::ALL
+        .into_iter()
+        .map(|d| {
+            let peak_garden_plots = {
+                let (row, col) = match d {
+                    Direction::Right => (start_border_distance, 0),
+                    Direction::Up => (size - 1, start_border_distance),
+                    Direction::Left => (start_border_distance, size - 1),
+                    Direction::Down => (0, start_border_distance),
                 };
+                let peak_start = row * size + col;
+                garden.count_reachable_plots(peak_start, peak_steps)
+            };
 
-                // Count diagonal border of quadrant (the hypotenuse)
-                let diagonal_plots = {
-                    let (row, col) = match d {
-                        // Top left
-                        Direction::Right => (0, 0),
-                        // Bottom left
-                        Direction::Up => (garden.size - 1, 0),
-                        // Bottom right
-                        Direction::Left => (garden.size - 1, garden.size - 1),
-                        // Top right
-                        Direction::Down => (0, garden.size - 1),
-                    };
-                    let corner_start = row * garden.size + col;
-                    // Moving diagonally (origin is to the back, peak to the front):
-                    // Move left from center of peak
-                    let minor_diagonal = garden.count_reachable_plots(
-                        corner_start,
-                        peak_steps - start_border_distance - 1,
-                    );
-                    // Then move one garden backward from peak
-                    let major_diagonal = garden.count_reachable_plots(
-                        corner_start,
-                        peak_steps - start_border_distance - 1 + garden.size,
-                    );
-
-                    minor_diagonal + (minor_diagonal + major_diagonal) * full_gardens
+            // Count diagonal border of quadrant (the hypotenuse)
+            let diagonal_plots = {
+                let (row, col) = match d {
+                    // Top left
+                    Direction::Right => (0, 0),
+                    // Bottom left
+                    Direction::Up => (size - 1, 0),
+                    // Bottom right
+                    Direction::Left => (size - 1, size - 1),
+                    // Top right
+                    Direction::Down => (0, size - 1),
                 };
+                let corner_start = row * size + col;
+                // Moving diagonally (origin is to the back, peak to the front):
+                // Move left from center of peak
+                let minor_diagonal = garden
+                    .count_reachable_plots(corner_start, peak_steps - start_border_distance - 1);
+                // Then move one garden backward from peak
+                let major_diagonal = garden.count_reachable_plots(
+                    corner_start,
+                    peak_steps - start_border_distance - 1 + size,
+                );
 
-                peak_garden_plots + diagonal_plots
-            })
-            .sum::<usize>();
-        // Count middle + 4 triangular quadrants + diagonal borders
-        full_count + 4 * quadrant_full_plots + quadrant_border_plots
+                minor_diagonal + (minor_diagonal + major_diagonal) * full_gardens
+            };
+
+            peak_garden_plots + diagonal_plots
+        })
+        .sum::<usize>();
+    // Count middle + 4 triangular quadrants + diagonal borders
+    full_count + 4 * quadrant_full_plots + quadrant_border_plots
+}
+
+/// Garden plots reachable in exactly `steps` steps within the garden
+pub fn reachable_plots(garden: &Garden, steps: usize) -> usize {
+    garden.count_reachable_plots(garden.start, steps)
+}
+
+/// Garden plots reachable in exactly `steps` steps in the endlessly repeating garden
+pub fn run(garden: &Garden, steps: usize) -> usize {
+    if garden.fits_diamond(steps) {
+        count_diamond(garden, steps)
+    } else {
+        garden.count_reachable_plots_endless(steps)
     }
 }
 
-pub fn solution(input: PuzzleInput, part2: bool) -> String {
-    run(input, if part2 { 26501365 } else { 64 }).to_string()
+/// Garden plots reachable in exactly 64 steps
+pub fn part1(garden: &Garden) -> usize {
+    reachable_plots(garden, Params::defaults(Day21::PARAMS, 1).get("steps"))
+}
+
+/// Garden plots reachable in exactly 26501365 steps in the endlessly repeating garden
+pub fn part2(garden: &Garden) -> usize {
+    run(garden, Params::defaults(Day21::PARAMS, 2).get("steps"))
+}
+
+pub struct Day21;
+
+impl Puzzle for Day21 {
+    type Input = Garden;
+
+    const TITLE: &'static str = "Step Counter";
+
+    const PARAMS: &'static [Param] = &[Param::per_part::<usize>(
+        "steps",
+        "steps the elf walks",
+        ["64", "26501365"],
+    )];
+
+    fn parse(input: PuzzleInput) -> Result<Self::Input, ParseError> {
+        Garden::parse(input)
+    }
+
+    fn part1(garden: &Self::Input, params: &Params) -> Answer {
+        reachable_plots(garden, params.get("steps")).into()
+    }
+
+    fn part2(garden: &Self::Input, params: &Params) -> Answer {
+        run(garden, params.get("steps")).into()
+    }
+
+    fn assumptions(garden: &Self::Input, part2: bool, params: &Params) -> Vec<Assumption> {
+        if part2 {
+            garden.assumptions(params.get("steps"))
+        } else {
+            // Part 1 stays within the garden
+            vec![]
+        }
+    }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::ParamOverride;
+
     const EXAMPLE: &str = r"...........
 .....###.#.
 .###.##..#.
@@ -276,22 +350,67 @@ mod test {
 
     #[test]
     fn test_endless() {
-        let garden = Garden::parse(PuzzleInput::from(EXAMPLE));
+        let garden = Garden::parse(PuzzleInput::from(EXAMPLE)).unwrap();
         assert_eq!(garden.count_reachable_plots_endless(6), 16);
         assert_eq!(garden.count_reachable_plots_endless(10), 50);
+        assert_eq!(garden.count_reachable_plots_endless(50), 1594);
         assert_eq!(garden.count_reachable_plots_endless(100), 6536);
         assert_eq!(garden.count_reachable_plots_endless(500), 167004);
-        //assert_eq!(garden.count_reachable_plots_endless(1000), 668697);
-        //assert_eq!(garden.count_reachable_plots_endless(5000), 16733044);
+        assert_eq!(garden.count_reachable_plots_endless(1000), 668697);
+        assert_eq!(garden.count_reachable_plots_endless(5000), 16733044);
+        assert_eq!(run(&garden, 5000), 16733044);
+    }
+
+    #[test]
+    fn test_params() {
+        let garden = Day21::parse(EXAMPLE.into()).unwrap();
+        let steps = |part, steps: &str| {
+            let overrides = [format!("steps={steps}").parse().unwrap()];
+            Params::new(Day21::PARAMS, part, &overrides).unwrap()
+        };
+        assert_eq!(
+            Day21::part1(&garden, &steps(1, "6")),
+            Answer::from(16_usize)
+        );
+        assert_eq!(
+            Day21::part2(&garden, &steps(2, "100")),
+            Answer::from(6536_usize)
+        );
+    }
+
+    #[test]
+    fn test_endless_any_start() {
+        let corner_example = EXAMPLE.replace('S', ".").replacen('.', "S", 1);
+        let garden = Garden::parse(corner_example.lines().map(String::from)).unwrap();
+        assert!(!garden.fits_diamond(5 + 11 * 5));
+        assert_eq!(run(&garden, 7), 39);
+        assert_eq!(run(&garden, 100), 6709);
+        assert_eq!(run(&garden, 500), 167697);
+
+        let border_example = r".............
+...###..#.#..
+..##..#...#..
+..#.###..S...
+.....#.#..#..
+.###.#.......
+....#.#......
+..###..##....
+....#.#..#...
+.#.#.#.......
+.#.#......##.
+.........#...
+.............";
+        let garden = Garden::parse(PuzzleInput::from(border_example)).unwrap();
+        assert_eq!(run(&garden, 1001), 740610);
     }
 
     macro_rules! run_compare {
         ($example:expr,$steps:expr) => {
             println!("___STEPS: {}", $steps);
             let steps = $steps;
-            let garden = Garden::parse(PuzzleInput::from($example));
+            let garden = Garden::parse(PuzzleInput::from($example)).unwrap();
             assert_eq!(
-                run($example.into(), steps),
+                run(&garden, steps),
                 garden.count_reachable_plots_endless(steps)
             );
         };
@@ -336,4 +455,45 @@ mod test {
         run_compare!(simplify_example, 5 + 11 * 4);
         run_compare!(simplify_example, 5 + 11 * 5);
     }
+
+    #[test]
+    fn test_rectangular() {
+        let rectangular_example = r".###.##..#.
+..#.#...#..
+....#.#....
+.##..S####.
+.##..#...#.
+.......##..
+.##.#.####.";
+        let garden = Garden::parse(PuzzleInput::from(rectangular_example)).unwrap();
+        assert_eq!(run(&garden, 2), 4);
+        assert_eq!(garden.count_reachable_plots_endless(6), 15);
+        assert_eq!(garden.count_reachable_plots_endless(10), 35);
+        assert_eq!(garden.count_reachable_plots_endless(25), 269);
+        assert_eq!(run(&garden, 100), 4600);
+        assert_eq!(run(&garden, 333), 51054);
+    }
+
+    #[test]
+    fn test_assumptions() {
+        let holds_with = |example, part, overrides: &[ParamOverride]| {
+            let params = Params::new(Day21::PARAMS, part, overrides).unwrap();
+            Day21::assumptions(&Day21::parse(example).unwrap(), part == 2, &params)
+                .into_iter()
+                .map(|a| a.holds)
+                .collect::<Vec<_>>()
+        };
+        let holds = |example| holds_with(example, 2, &[]);
+        // The example has rocks beside the start and 11 does not divide `26501365 - 5`
+        assert_eq!(holds(EXAMPLE.into()), [true, true, false, false]);
+        assert_eq!(holds("#..\n.S.\n...".into()), [true, true, true, true]);
+        assert_eq!(holds("S..\n...".into()), [false, false, true, true]);
+        // 27 steps end at the border of the second garden beyond the start
+        let steps = ["steps=27".parse().unwrap()];
+        assert_eq!(
+            holds_with(EXAMPLE.into(), 2, &steps),
+            [true, true, false, true]
+        );
+        assert!(holds_with(EXAMPLE.into(), 1, &[]).is_empty());
+    }
 }
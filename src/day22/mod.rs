@@ -1,33 +1,49 @@
+//! Day 22: Sand Slabs
+//!
+//! Each [`Brick`] holds its snapshot coordinates; [`settle`] lets them fall and
+//! gives the bricks each brick rests on.
+
 use std::{collections::HashSet, ops::Range};
 
 use itertools::Itertools;
 
-use crate::PuzzleInput;
+use crate::{parse_token, Answer, Params, ParseError, Puzzle, PuzzleInput};
 
 #[derive(Debug, Clone)]
-struct Brick {
+pub struct Brick {
     lateral: Range<usize>,
     colateral: Range<usize>,
     elevation: Range<usize>,
 }
 
 impl Brick {
-    fn parse(line: &str) -> Self {
-        let ((x, y, z), (x2, y2, z2)) = line
-            .splitn(2, '~')
-            .map(|s| {
-                s.split(',')
-                    .map(|n| n.parse::<usize>().unwrap())
-                    .collect_tuple()
-                    .unwrap()
-            })
-            .collect_tuple()
-            .unwrap();
-        Self {
+    fn parse(line: usize, text: &str) -> Result<Self, ParseError> {
+        let (start, end) = text
+            .split_once('~')
+            .ok_or_else(|| ParseError::at_end(line, text, "'~' between brick ends"))?;
+        let parse_coords = |coords: &str| -> Result<(usize, usize, usize), ParseError> {
+            coords
+                .split(',')
+                .map(|n| parse_token(line, text, n, "coordinate"))
+                .collect::<Result<Vec<usize>, _>>()?
+                .into_iter()
+                .collect_tuple()
+                .ok_or_else(|| ParseError::at(line, text, coords, "'<x>,<y>,<z>' coordinates"))
+        };
+        let ((x, y, z), (x2, y2, z2)) = (parse_coords(start)?, parse_coords(end)?);
+        if x > x2 || y > y2 || z > z2 {
+            return Err(ParseError::at(
+                line,
+                text,
+                end,
+                "end coordinates after start",
+            ));
+        }
+        Ok(Self {
             lateral: x..x2 + 1,
             colateral: y..y2 + 1,
             elevation: z..z2 + 1,
-        }
+        })
     }
 
     fn height(&self) -> usize {
@@ -41,7 +57,9 @@ impl Brick {
     }
 }
 
-fn foundations_of_brick(bricks: &[Brick]) -> Vec<HashSet<usize>> {
+/// Let all bricks fall until they rest on the ground or other bricks.
+/// Returns the indices of the bricks which each brick rests on.
+pub fn settle(bricks: &[Brick]) -> Vec<HashSet<usize>> {
     let bricks_sorted = bricks
         .iter()
         .enumerate()
@@ -86,10 +104,8 @@ fn foundations_of_brick(bricks: &[Brick]) -> Vec<HashSet<usize>> {
     foundations
 }
 
-fn run(input: PuzzleInput, part2: bool) -> usize {
-    let bricks = input.map(|line| Brick::parse(&line)).collect_vec();
-
-    let foundations = foundations_of_brick(&bricks);
+fn run(bricks: &[Brick], part2: bool) -> usize {
+    let foundations = settle(bricks);
     let mut brick_loads = (0..foundations.len()).map(|_| vec![]).collect_vec();
     for (index, foundations) in foundations.iter().enumerate() {
         for foundation in foundations {
@@ -125,8 +141,42 @@ fn run(input: PuzzleInput, part2: bool) -> usize {
     }
 }
 
-pub fn solution(input: PuzzleInput, part2: bool) -> String {
-    run(input, part2).to_string()
+/// Number of bricks which can be disintegrated without any other brick falling
+pub fn part1(bricks: &[Brick]) -> usize {
+    run(bricks, false)
+}
+
+/// Sum of the numbers of other bricks which fall when disintegrating each brick
+pub fn part2(bricks: &[Brick]) -> usize {
+    run(bricks, true)
+}
+
+pub struct Day22;
+
+impl Puzzle for Day22 {
+    type Input = Vec<Brick>;
+
+    const TITLE: &'static str = "Sand Slabs";
+
+    fn parse(input: PuzzleInput) -> Result<Self::Input, ParseError> {
+        let bricks: Vec<Brick> = input
+            .enumerate()
+            .filter(|(_, text)| !text.is_empty())
+            .map(|(line, text)| Brick::parse(line, &text))
+            .try_collect()?;
+        if bricks.is_empty() {
+            return Err(ParseError::new(0, 0, "brick"));
+        }
+        Ok(bricks)
+    }
+
+    fn part1(bricks: &Self::Input, _params: &Params) -> Answer {
+        part1(bricks).into()
+    }
+
+    fn part2(bricks: &Self::Input, _params: &Params) -> Answer {
+        part2(bricks).into()
+    }
 }
 
 #[cfg(test)]
@@ -142,7 +192,16 @@ mod test {
 2,0,5~2,2,5
 0,1,6~2,1,6
 1,1,8~1,1,9";
-        assert_eq!(run(test_input.into(), false), 5);
-        assert_eq!(run(test_input.into(), true), 7);
+        let bricks = Day22::parse(test_input.into()).unwrap();
+        assert_eq!(run(&bricks, false), 5);
+        assert_eq!(run(&bricks, true), 7);
+    }
+
+    #[test]
+    fn test_parse_error() {
+        assert_eq!(
+            Day22::parse("".into()).err(),
+            Some(ParseError::new(0, 0, "brick"))
+        );
     }
 }
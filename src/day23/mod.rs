@@ -1,42 +1,17 @@
+//! Day 23: A Long Walk
+//!
+//! The [`TrailMap`] holds the paths, forests and slopes of the island.
+
 use std::{
     collections::{BinaryHeap, HashMap, HashSet, VecDeque},
     rc::Rc,
 };
 
 use itertools::Itertools;
-use num_integer::{Integer, Roots};
-
-use crate::PuzzleInput;
 
-type Position = usize;
-
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-enum Direction {
-    Right,
-    Up,
-    Left,
-    Down,
-}
-const DIRS: [Direction; 4] = [
-    Direction::Right,
-    Direction::Up,
-    Direction::Left,
-    Direction::Down,
-];
-
-impl Direction {
-    fn backward(&self, d: &Direction) -> bool {
-        match self {
-            Direction::Right => matches!(d, Direction::Left),
-            Direction::Up => matches!(d, Direction::Down),
-            Direction::Left => matches!(d, Direction::Right),
-            Direction::Down => matches!(d, Direction::Up),
-        }
-    }
-    fn forward(&self) -> impl Iterator<Item = Direction> + '_ {
-        DIRS.into_iter().filter(|d| !self.backward(d))
-    }
-}
+use crate::{
+    Answer, Direction, Grid, GridShape, Params, ParseError, Position, Puzzle, PuzzleInput,
+};
 
 #[derive(Debug)]
 enum Slot {
@@ -50,11 +25,10 @@ impl From<char> for Slot {
         match value {
             '.' => Self::Path,
             '#' => Self::Forest,
-            '>' => Self::Slope(Direction::Right),
-            '^' => Self::Slope(Direction::Up),
-            '<' => Self::Slope(Direction::Left),
-            'v' => Self::Slope(Direction::Down),
-            _ => panic!("unexpected slot: {}", value),
+            _ => Self::Slope(
+                Direction::from_arrow(value)
+                    .unwrap_or_else(|| panic!("unexpected slot: {}", value)),
+            ),
         }
     }
 }
@@ -129,6 +103,34 @@ impl PartialEq for PathCandidate {
 }
 impl Eq for PathCandidate {}
 
+/// Map of hiking trail slots
+pub struct TrailMap {
+    slots: Grid<Slot>,
+    /// Path slots in the top and bottom rows
+    start: Position,
+    end: Position,
+}
+
+impl TrailMap {
+    fn parse(lines: impl Iterator<Item = String>) -> Result<Self, ParseError> {
+        let slots = Grid::parse(lines.enumerate(), ".#>^<v", Slot::from)?;
+        let path_in_row = |row| {
+            slots
+                .row(row)
+                .iter()
+                .position(|slot| matches!(slot, Slot::Path))
+                .map(|col| slots.position(row, col))
+                .ok_or_else(|| ParseError::new(row, 0, "'.' path slot"))
+        };
+        let start = path_in_row(0)?;
+        if slots.height() < 2 {
+            return Err(ParseError::new(slots.height(), 0, "bottom row"));
+        }
+        let end = path_in_row(slots.height() - 1)?;
+        Ok(Self { slots, start, end })
+    }
+}
+
 struct Graph {
     edges: HashMap<Position, Vec<Position>>,
     edge_weights: HashMap<Edge, usize>,
@@ -137,15 +139,9 @@ struct Graph {
 }
 
 impl Graph {
-    fn parse(lines: impl Iterator<Item = String>, dry: bool) -> Self {
-        let mut slots = vec![];
-        for line in lines {
-            slots.extend(line.chars().map(|c| c.into()));
-        }
-        let size = slots.len().sqrt();
-
-        let start_pos = 1;
-        let end_pos = size * size - 2;
+    fn new(trail_map: &TrailMap, dry: bool) -> Self {
+        let slots = &trail_map.slots;
+        let (start_pos, end_pos) = (trail_map.start, trail_map.end);
         let mut edges: HashMap<Position, Vec<Position>> = HashMap::new();
         let mut edge_weights: HashMap<Edge, usize> = HashMap::new();
         let mut add_edge = |p1: Position, p2: Position, weight: usize| {
@@ -172,13 +168,8 @@ impl Graph {
             [(start_pos, Direction::Down)].into_iter().collect();
         while let Some((edge_start, first_direction)) = candidates.pop_front() {
             // Find next heads
-            let advance = |d, p| {
-                let next_end = match d {
-                    Direction::Right => p + 1,
-                    Direction::Up => p - size,
-                    Direction::Left => p - 1,
-                    Direction::Down => p + size,
-                };
+            let advance = |d: Direction, p| {
+                let next_end = slots.step(p, d)?;
                 if match &slots[next_end] {
                     Slot::Path => true,
                     Slot::Forest => false,
@@ -189,11 +180,13 @@ impl Graph {
                     None
                 }
             };
-            let (mut pos, mut from_direction) = advance(first_direction, edge_start).unwrap();
+            let Some((mut pos, mut from_direction)) = advance(first_direction, edge_start) else {
+                continue;
+            };
             let mut step = 1;
             loop {
                 let heads = from_direction
-                    .forward()
+                    .ahead()
                     .filter_map(|d| advance(d, pos))
                     .collect_vec();
                 if heads.len() == 1 {
@@ -203,20 +196,16 @@ impl Graph {
                 if pos == start_pos {
                     break;
                 }
-
-                assert!(
-                    !heads.is_empty(),
-                    "should have no dead ends from {:?} at {:?}",
-                    edge_start.div_rem(&size),
-                    pos.div_rem(&size),
-                );
-
                 if pos == end_pos || heads.len() > 1 {
                     if add_edge(edge_start, pos, step) && pos != end_pos {
                         candidates.extend(heads.into_iter().map(|(_, dir)| (pos, dir)));
                     }
                     break;
                 }
+                if heads.is_empty() {
+                    // A dead end is not part of any hike
+                    break;
+                }
             }
         }
         Self {
@@ -232,8 +221,10 @@ impl Graph {
         cand: &'a PathCandidate,
     ) -> impl Iterator<Item = PathCandidate> + 'a {
         cand.advance(
-            self.edges[&cand.head]
-                .iter()
+            self.edges
+                .get(&cand.head)
+                .into_iter()
+                .flatten()
                 .map(|end| {
                     let edge = (cand.head.min(*end), cand.head.max(*end));
                     (*end, self.edge_weights[&edge])
@@ -265,8 +256,10 @@ impl Graph {
             let mut reachable_edges: HashSet<Edge> = HashSet::new();
             while let Some(visit) = visits.pop() {
                 visits.extend(
-                    self.edges[&visit]
-                        .iter()
+                    self.edges
+                        .get(&visit)
+                        .into_iter()
+                        .flatten()
                         .filter(|&&end| {
                             let edge = (visit.min(end), visit.max(end));
                             initial.residual_edge_weights.contains_key(&edge)
@@ -286,7 +279,7 @@ impl Graph {
         }
     }
 
-    /// Exhaustively search for longest paths:
+    /// Exhaustively search for longest paths (0 if no path reaches the end):
     /// Visiting best greedy paths first.
     /// (For polynomial-time solution https://en.wikipedia.org/wiki/Longest_path_problem
     /// says it has to be a specific graph, possibly, a cactus graph?)
@@ -321,13 +314,39 @@ impl Graph {
     }
 }
 
-fn run(input: PuzzleInput, part2: bool) -> usize {
-    let graph = Graph::parse(input, part2);
+fn run(trail_map: &TrailMap, part2: bool) -> usize {
+    let graph = Graph::new(trail_map, part2);
     graph.longest_path_length()
 }
 
-pub fn solution(input: PuzzleInput, part2: bool) -> String {
-    run(input, part2).to_string()
+/// Steps of the longest hike which does not climb steep slopes
+pub fn part1(trail_map: &TrailMap) -> usize {
+    run(trail_map, false)
+}
+
+/// Steps of the longest hike when slopes can be climbed
+pub fn part2(trail_map: &TrailMap) -> usize {
+    run(trail_map, true)
+}
+
+pub struct Day23;
+
+impl Puzzle for Day23 {
+    type Input = TrailMap;
+
+    const TITLE: &'static str = "A Long Walk";
+
+    fn parse(input: PuzzleInput) -> Result<Self::Input, ParseError> {
+        TrailMap::parse(input)
+    }
+
+    fn part1(trail_map: &Self::Input, _params: &Params) -> Answer {
+        part1(trail_map).into()
+    }
+
+    fn part2(trail_map: &Self::Input, _params: &Params) -> Answer {
+        part2(trail_map).into()
+    }
 }
 
 #[cfg(test)]
@@ -360,7 +379,60 @@ mod test {
 #.....###...###...#...#
 #####################.#
 ";
-        assert_eq!(run(test_input.into(), false), 94);
-        assert_eq!(run(test_input.into(), true), 154);
+        let trail_map = TrailMap::parse(PuzzleInput::from(test_input)).unwrap();
+        assert_eq!(run(&trail_map, false), 94);
+        assert_eq!(run(&trail_map, true), 154);
+    }
+
+    #[test]
+    fn test_rectangular() {
+        // Trail example with the start and end shifted by an extra forest column
+        let test_input = r"##.#####################
+##.......#########...###
+########.#########.#.###
+####.....#.>.>.###.#.###
+####v#####.#v#.###.#.###
+####.>...#.#.#.....#...#
+####v###.#.#.#########.#
+####...#.#.#.......#...#
+######.#.#.#######.#.###
+##.....#.#.#.......#...#
+##.#####.#.#.#########v#
+##.#...#...#...###...>.#
+##.#.#v#######v###.###v#
+##...#.>.#...>.>.#.###.#
+######v#.#.###v#.#.###.#
+##.....#...#...#.#.#...#
+##.#########.###.#.#.###
+##...###...#...#...#.###
+####.###.#.###v#####v###
+##...#...#.#.>.>.#.>.###
+##.###.###.#.###.#.#v###
+##.....###...###...#...#
+######################.#
+";
+        let trail_map = TrailMap::parse(PuzzleInput::from(test_input)).unwrap();
+        assert_eq!(run(&trail_map, false), 94);
+        assert_eq!(run(&trail_map, true), 154);
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let err = |input: &'static str| Day23::parse(input.into()).err();
+        assert_eq!(err(""), Some(ParseError::new(0, 0, "'.' path slot")));
+        assert_eq!(err("#.#"), Some(ParseError::new(1, 0, "bottom row")));
+        assert_eq!(
+            err("#.#\n###"),
+            Some(ParseError::new(1, 0, "'.' path slot"))
+        );
+    }
+
+    #[test]
+    fn test_dead_ends() {
+        let trail_map = |input: &'static str| Day23::parse(input.into()).unwrap();
+        assert_eq!(part1(&trail_map("#.#\n#.#")), 1);
+        assert_eq!(part2(&trail_map("#.###\n#...#\n#.#.#\n###.#")), 5);
+        // No hike reaches the bottom row
+        assert_eq!(part2(&trail_map("#.#\n###\n#.#")), 0);
     }
 }
@@ -0,0 +1,248 @@
+//! Day 24: Never Tell Me The Odds
+//!
+//! Each [`Hailstone`] holds its position and velocity.
+
+use std::ops::RangeInclusive;
+
+use itertools::Itertools;
+
+use crate::{parse_token, Answer, Params, ParseError, Puzzle, PuzzleInput};
+
+type Vec3 = [i128; 3];
+
+fn sub(a: Vec3, b: Vec3) -> Vec3 {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+fn add_scaled(a: Vec3, b: Vec3, factor: i128) -> Vec3 {
+    [
+        a[0] + b[0] * factor,
+        a[1] + b[1] * factor,
+        a[2] + b[2] * factor,
+    ]
+}
+
+fn dot(a: Vec3, b: Vec3) -> i128 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+fn cross(a: Vec3, b: Vec3) -> Vec3 {
+    [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ]
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Hailstone {
+    position: Vec3,
+    velocity: Vec3,
+}
+
+impl Hailstone {
+    fn parse(line: usize, text: &str) -> Result<Self, ParseError> {
+        let (position, velocity) = text
+            .split_once('@')
+            .ok_or_else(|| ParseError::at_end(line, text, "'@' between position and velocity"))?;
+        let parse_vec = |part: &str, expected: &str| -> Result<Vec3, ParseError> {
+            let coords: Vec<i64> = part
+                .split(',')
+                .map(|n| parse_token(line, text, n.trim(), expected))
+                .try_collect()?;
+            coords
+                .into_iter()
+                .map(i128::from)
+                .collect_tuple()
+                .map(|(x, y, z)| [x, y, z])
+                .ok_or_else(|| ParseError::at(line, text, part, "three coordinates"))
+        };
+        Ok(Self {
+            position: parse_vec(position, "position coordinate")?,
+            velocity: parse_vec(velocity, "velocity coordinate")?,
+        })
+    }
+
+    fn in_frame_of(&self, other: &Hailstone) -> Hailstone {
+        Hailstone {
+            position: sub(self.position, other.position),
+            velocity: sub(self.velocity, other.velocity),
+        }
+    }
+}
+
+/// Whether the future xy-paths of two hailstones cross inside `area`.
+/// The crossing is compared exactly by scaling the test area with the determinant.
+fn paths_cross_in(a: &Hailstone, b: &Hailstone, area: &RangeInclusive<i128>) -> bool {
+    let [px, py, _] = sub(b.position, a.position);
+    let [ax, ay, _] = a.velocity;
+    let [bx, by, _] = b.velocity;
+    let det = ax * by - ay * bx;
+    if det == 0 {
+        return false;
+    }
+    let sign = det.signum();
+    let (det, t_a, t_b) = (
+        det * sign,
+        (px * by - py * bx) * sign,
+        (px * ay - py * ax) * sign,
+    );
+    if t_a < 0 || t_b < 0 {
+        return false;
+    }
+    let in_area = |position: i128, velocity: i128| {
+        let scaled = position * det + velocity * t_a;
+        (area.start() * det..=area.end() * det).contains(&scaled)
+    };
+    in_area(a.position[0], ax) && in_area(a.position[1], ay)
+}
+
+fn count_crossings(hailstones: &[Hailstone], area: RangeInclusive<i128>) -> usize {
+    hailstones
+        .iter()
+        .tuple_combinations()
+        .filter(|(a, b)| paths_cross_in(a, b, &area))
+        .count()
+}
+
+/// Time at which `rock` collides with `hailstone`, if it does at a whole nanosecond from now on
+fn collision_time(rock: &Hailstone, hailstone: &Hailstone) -> Option<i128> {
+    let Hailstone { position, velocity } = rock.in_frame_of(hailstone);
+    // Without relative velocity both move together and collide only if they start together
+    let Some(axis) = velocity.iter().position(|&v| v != 0) else {
+        return (position == [0; 3]).then_some(0);
+    };
+    let time = -position[axis] / velocity[axis];
+    (time >= 0 && add_scaled(position, velocity, time) == [0; 3]).then_some(time)
+}
+
+/// Whether the path of `rock` collides with every hailstone
+fn hits_all(hailstones: &[Hailstone], rock: &Hailstone) -> bool {
+    hailstones
+        .iter()
+        .all(|hailstone| collision_time(rock, hailstone).is_some())
+}
+
+/// Find the rock from three hailstones in the frame of the first one.
+/// There the rock passes the origin, so it lies in the planes spanned by the
+/// origin and the paths of the two other hailstones.
+fn rock_from(h0: &Hailstone, h1: &Hailstone, h2: &Hailstone) -> Option<Hailstone> {
+    let (h1, h2) = (h1.in_frame_of(h0), h2.in_frame_of(h0));
+    let n1 = cross(h1.position, h1.velocity);
+    let n2 = cross(h2.position, h2.velocity);
+    let collision_time = |h: &Hailstone, plane: Vec3| {
+        let denominator = dot(h.velocity, plane);
+        let numerator = -dot(h.position, plane);
+        (denominator != 0 && numerator % denominator == 0).then(|| numerator / denominator)
+    };
+    let t1 = collision_time(&h1, n2)?;
+    let t2 = collision_time(&h2, n1)?;
+    if t1 == t2 {
+        return None;
+    }
+    let c1 = add_scaled(h1.position, h1.velocity, t1);
+    let c2 = add_scaled(h2.position, h2.velocity, t2);
+    let delta = sub(c2, c1);
+    if delta.iter().any(|d| d % (t2 - t1) != 0) {
+        return None;
+    }
+    let velocity = delta.map(|d| d / (t2 - t1));
+    let position = add_scaled(c1, velocity, -t1);
+    Some(Hailstone {
+        position: add_scaled(position, h0.position, 1),
+        velocity: add_scaled(velocity, h0.velocity, 1),
+    })
+}
+
+fn throw_position(hailstones: &[Hailstone]) -> Option<Vec3> {
+    hailstones
+        .iter()
+        .tuple_combinations()
+        .filter_map(|(h0, h1, h2)| rock_from(h0, h1, h2))
+        .find(|rock| hits_all(hailstones, rock))
+        .map(|rock| rock.position)
+}
+
+/// Number of future crossings of hailstone paths within the test area
+pub fn part1(hailstones: &[Hailstone]) -> usize {
+    count_crossings(hailstones, 200000000000000..=400000000000000)
+}
+
+/// Sum of the coordinates of the position to throw a rock from which hits all hailstones,
+/// if there is any
+pub fn part2(hailstones: &[Hailstone]) -> Option<i128> {
+    throw_position(hailstones).map(|position| position.iter().sum())
+}
+
+pub struct Day24;
+
+impl Puzzle for Day24 {
+    type Input = Vec<Hailstone>;
+
+    const TITLE: &'static str = "Never Tell Me The Odds";
+
+    fn parse(input: PuzzleInput) -> Result<Self::Input, ParseError> {
+        input
+            .enumerate()
+            .filter(|(_, text)| !text.is_empty())
+            .map(|(line, text)| Hailstone::parse(line, &text))
+            .collect()
+    }
+
+    fn part1(hailstones: &Self::Input, _params: &Params) -> Answer {
+        part1(hailstones).into()
+    }
+
+    fn part2(hailstones: &Self::Input, _params: &Params) -> Answer {
+        part2(hailstones).map_or_else(
+            || Answer::Unsolved("no throw hits all hailstones".into()),
+            Answer::from,
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_run() {
+        let test_input = r"19, 13, 30 @ -2,  1, -2
+18, 19, 22 @ -1, -1, -2
+20, 25, 34 @ -2, -2, -4
+12, 31, 28 @ -1, -2, -1
+20, 19, 15 @  1, -5, -3";
+        let hailstones = Day24::parse(test_input.into()).unwrap();
+        assert_eq!(count_crossings(&hailstones, 7..=27), 2);
+        assert_eq!(throw_position(&hailstones), Some([24, 13, 10]));
+        assert_eq!(
+            Day24::part2(&hailstones, &Params::default()),
+            Answer::Integer(47)
+        );
+    }
+
+    #[test]
+    fn test_hits_all() {
+        let hailstone = |position, velocity| Hailstone { position, velocity };
+        let hailstones = [hailstone([0, 0, 0], [1, 0, 0])];
+        // Collides at time 5
+        assert!(hits_all(&hailstones, &hailstone([-5, 0, 0], [2, 0, 0])));
+        // Collided at time -5 only
+        assert!(!hits_all(&hailstones, &hailstone([5, 0, 0], [2, 0, 0])));
+        // Passes the hailstone between two nanoseconds
+        assert!(!hits_all(&hailstones, &hailstone([-1, 0, 0], [3, 0, 0])));
+        // Moves along with the hailstone
+        assert!(hits_all(&hailstones, &hailstone([0, 0, 0], [1, 0, 0])));
+        assert!(!hits_all(&hailstones, &hailstone([-1, 0, 0], [1, 0, 0])));
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let test_input = r"19, 13, 30 @ -2,  1, -2
+18, 19 @ -1, -1, -2";
+        assert_eq!(
+            Day24::parse(test_input.into()).unwrap_err(),
+            ParseError::new(1, 0, "three coordinates")
+        );
+    }
+}
@@ -0,0 +1,195 @@
+//! Day 25: Snowverload
+//!
+//! The [`WiringDiagram`] holds the connections between the components.
+
+use std::collections::{HashMap, VecDeque};
+
+use crate::{Answer, Params, ParseError, Puzzle, PuzzleInput};
+
+/// Undirected graph of components connected by wires
+pub struct WiringDiagram {
+    labels: Vec<String>,
+    wires: Vec<(usize, usize)>,
+    /// Wire indices connected to each component
+    adjacent_wires: Vec<Vec<usize>>,
+}
+
+/// Minimum cut separating the components into two groups
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cut<'a> {
+    pub wires: Vec<(&'a str, &'a str)>,
+    pub group_sizes: (usize, usize),
+}
+
+impl WiringDiagram {
+    fn parse(input: PuzzleInput) -> Result<Self, ParseError> {
+        let mut ids = HashMap::new();
+        let mut diagram = WiringDiagram {
+            labels: Vec::new(),
+            wires: Vec::new(),
+            adjacent_wires: Vec::new(),
+        };
+        let mut component_id = |diagram: &mut WiringDiagram, label: &str| {
+            *ids.entry(label.to_string()).or_insert_with(|| {
+                diagram.labels.push(label.to_string());
+                diagram.adjacent_wires.push(Vec::new());
+                diagram.labels.len() - 1
+            })
+        };
+        for (line, text) in input.enumerate().filter(|(_, text)| !text.is_empty()) {
+            let (component, connected) = text
+                .split_once(": ")
+                .ok_or_else(|| ParseError::at_end(line, &text, "': ' after component"))?;
+            if let Some(label) = std::iter::once(component)
+                .chain(connected.split(' '))
+                .find(|label| label.is_empty() || !label.chars().all(|c| c.is_ascii_lowercase()))
+            {
+                return Err(ParseError::at(line, &text, label, "component label"));
+            }
+            let from = component_id(&mut diagram, component);
+            for label in connected.split(' ') {
+                let to = component_id(&mut diagram, label);
+                diagram.adjacent_wires[from].push(diagram.wires.len());
+                diagram.adjacent_wires[to].push(diagram.wires.len());
+                diagram.wires.push((from, to));
+            }
+        }
+        Ok(diagram)
+    }
+
+    /// Find an augmenting path from `source` to `sink` in the residual graph of `flow`
+    /// (positive flow of a wire goes from its first to its second component).
+    /// Returns the components reachable from `source` if there is none.
+    fn augment(&self, flow: &mut [i8], source: usize, sink: usize) -> Result<(), Vec<bool>> {
+        let mut via_wire = vec![None; self.labels.len()];
+        let mut reached = vec![false; self.labels.len()];
+        reached[source] = true;
+        let mut queue = VecDeque::from([source]);
+        while let Some(component) = queue.pop_front() {
+            for &wire in &self.adjacent_wires[component] {
+                let (a, b) = self.wires[wire];
+                let (next, residual) = if a == component {
+                    (b, 1 - flow[wire])
+                } else {
+                    (a, 1 + flow[wire])
+                };
+                if residual > 0 && !reached[next] {
+                    reached[next] = true;
+                    via_wire[next] = Some(wire);
+                    queue.push_back(next);
+                }
+            }
+        }
+        if !reached[sink] {
+            return Err(reached);
+        }
+        let mut component = sink;
+        while let Some(wire) = via_wire[component] {
+            let (a, b) = self.wires[wire];
+            if b == component {
+                flow[wire] += 1;
+                component = a;
+            } else {
+                flow[wire] -= 1;
+                component = b;
+            }
+        }
+        Ok(())
+    }
+
+    /// Minimum cut between `source` and `sink` if it has at most `max_size` wires
+    fn min_cut_between(&self, source: usize, sink: usize, max_size: usize) -> Option<Vec<bool>> {
+        let mut flow = vec![0; self.wires.len()];
+        for _ in 0..=max_size {
+            if let Err(reached) = self.augment(&mut flow, source, sink) {
+                return Some(reached);
+            }
+        }
+        None
+    }
+
+    /// Find a cut of at most `max_size` wires with Edmonds-Karp max-flow from the
+    /// first component to each other component until one is separated
+    pub fn min_cut(&self, max_size: usize) -> Option<Cut<'_>> {
+        let reached =
+            (1..self.labels.len()).find_map(|sink| self.min_cut_between(0, sink, max_size))?;
+        let group_size = reached.iter().filter(|&&r| r).count();
+        let wires = self
+            .wires
+            .iter()
+            .filter(|(a, b)| reached[*a] != reached[*b])
+            .map(|&(a, b)| (self.labels[a].as_str(), self.labels[b].as_str()))
+            .collect();
+        Some(Cut {
+            wires,
+            group_sizes: (group_size, self.labels.len() - group_size),
+        })
+    }
+}
+
+fn run(diagram: &WiringDiagram) -> usize {
+    diagram
+        .min_cut(3)
+        .map_or(0, |cut| cut.group_sizes.0 * cut.group_sizes.1)
+}
+
+/// Product of the sizes of the two groups after cutting three wires
+pub fn part1(diagram: &WiringDiagram) -> usize {
+    run(diagram)
+}
+
+pub struct Day25;
+
+impl Puzzle for Day25 {
+    type Input = WiringDiagram;
+
+    const TITLE: &'static str = "Snowverload";
+
+    const PARTS: u16 = 1;
+
+    fn parse(input: PuzzleInput) -> Result<Self::Input, ParseError> {
+        WiringDiagram::parse(input)
+    }
+
+    fn part1(diagram: &Self::Input, _params: &Params) -> Answer {
+        part1(diagram).into()
+    }
+
+    /// Day 25 has no second puzzle
+    fn part2(_: &Self::Input, _params: &Params) -> Answer {
+        Answer::Text(String::new())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_run() {
+        let test_input = r"jqt: rhn xhk nvd
+rsh: frs pzl lsr
+xhk: hfx
+cmg: qnr nvd lhk bvb
+rhn: xhk bvb hfx
+bvb: xhk hfx
+pzl: lsr hfx nvd
+qnr: nvd
+ntq: jqt hfx bvb xhk
+nvd: lhk
+lsr: lhk
+rzs: qnr cmg lsr rsh
+frs: qnr lhk lsr";
+        let diagram = Day25::parse(test_input.into()).unwrap();
+        assert_eq!(run(&diagram), 54);
+        let mut cut = diagram.min_cut(3).unwrap();
+        cut.wires.iter_mut().for_each(|(a, b)| {
+            if a > b {
+                std::mem::swap(a, b)
+            }
+        });
+        cut.wires.sort();
+        assert_eq!(cut.wires, [("bvb", "cmg"), ("hfx", "pzl"), ("jqt", "nvd")]);
+        assert!(diagram.min_cut(2).is_none());
+    }
+}
@@ -1,11 +1,15 @@
-use std::{ops::Range, rc::Rc};
+//! Day 3: Gear Ratios
+//!
+//! Each [`GridLine`] holds the positions of the numbers and symbols of an engine schematic line.
+
+use std::ops::Range;
 
 use itertools::Itertools;
 use regex::Regex;
 
-use crate::PuzzleInput;
+use crate::{Answer, Params, ParseError, Puzzle, PuzzleInput};
 
-struct GridLine {
+pub struct GridLine {
     symbol_ranges: Vec<Range<usize>>,
     number_ranges: Vec<Range<usize>>,
     line: String,
@@ -66,34 +70,74 @@ fn gear_ratio(gear_position: usize, lines: [&GridLine; 3]) -> Option<u32> {
         .map(|(a, b)| a * b)
 }
 
-fn run(input: PuzzleInput, part2: bool) -> u32 {
-    let number_regex = Regex::new(r"\d+").unwrap();
-    let symbol_regex = Regex::new(if part2 { r"\*" } else { r"[^\.\d]" }).unwrap();
+fn run(grid_lines: &[GridLine], part2: bool) -> u32 {
     // Pad start and end of grid with an empty line
-    let empty_line = Rc::new(GridLine::new("".into(), &symbol_regex, &number_regex));
-    [empty_line.clone()]
+    let empty_line = GridLine {
+        symbol_ranges: vec![],
+        number_ranges: vec![],
+        line: "".into(),
+    };
+    [&empty_line]
         .into_iter()
-        .chain(input.map(|line| Rc::new(GridLine::new(line, &symbol_regex, &number_regex))))
-        .chain([empty_line])
+        .chain(grid_lines)
+        .chain([&empty_line])
         .tuple_windows()
         .map(|(prev, cur, next)| {
             if part2 {
                 cur.symbol_ranges
                     .iter()
-                    .filter_map(|r| gear_ratio(r.start, [&prev, &cur, &next]))
+                    .filter(|r| &cur.line[r.start..r.end] == "*")
+                    .filter_map(|r| gear_ratio(r.start, [prev, cur, next]))
                     .sum::<u32>()
             } else {
                 cur.number_ranges
                     .iter()
-                    .filter_map(|r| part_number(&cur.line[r.clone()], r, [&prev, &cur, &next]))
+                    .filter_map(|r| part_number(&cur.line[r.clone()], r, [prev, cur, next]))
                     .sum()
             }
         })
         .sum()
 }
 
-pub fn solution(input: PuzzleInput, part2: bool) -> String {
-    run(input, part2).to_string()
+/// Sum of all part numbers, which are adjacent to a symbol
+pub fn part1(grid_lines: &[GridLine]) -> u32 {
+    run(grid_lines, false)
+}
+
+/// Sum of the gear ratios of all `*` symbols adjacent to exactly two part numbers
+pub fn part2(grid_lines: &[GridLine]) -> u32 {
+    run(grid_lines, true)
+}
+
+pub struct Day3;
+
+impl Puzzle for Day3 {
+    type Input = Vec<GridLine>;
+
+    const TITLE: &'static str = "Gear Ratios";
+
+    fn parse(input: PuzzleInput) -> Result<Self::Input, ParseError> {
+        let number_regex = Regex::new(r"\d+").unwrap();
+        let symbol_regex = Regex::new(r"[^\.\d]").unwrap();
+        input
+            .enumerate()
+            .map(|(line, text)| {
+                if let Some(m) = number_regex.find_iter(&text).find(|m| m.len() > 9) {
+                    Err(ParseError::new(line, m.start(), "part number below 10^9"))
+                } else {
+                    Ok(GridLine::new(text, &symbol_regex, &number_regex))
+                }
+            })
+            .collect()
+    }
+
+    fn part1(grid_lines: &Self::Input, _params: &Params) -> Answer {
+        part1(grid_lines).into()
+    }
+
+    fn part2(grid_lines: &Self::Input, _params: &Params) -> Answer {
+        part2(grid_lines).into()
+    }
 }
 
 #[cfg(test)]
@@ -112,7 +156,8 @@ mod test {
 ......755.
 ...$.*....
 .664.598..";
-        assert_eq!(run(test_input.into(), false), 4361);
-        assert_eq!(run(test_input.into(), true), 467835);
+        let grid_lines = Day3::parse(test_input.into()).unwrap();
+        assert_eq!(run(&grid_lines, false), 4361);
+        assert_eq!(run(&grid_lines, true), 467835);
     }
 }
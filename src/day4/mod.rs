@@ -1,32 +1,35 @@
-use std::collections::{HashSet, VecDeque};
+//! Day 4: Scratchcards
+//!
+//! Each [`Card`] holds its winning numbers and the numbers you have.
 
-use itertools::Itertools;
+use std::collections::{HashSet, VecDeque};
 
-use crate::PuzzleInput;
+use crate::{parse_token, Answer, Params, ParseError, Puzzle, PuzzleInput};
 
-struct Card {
+pub struct Card {
     winning_numbers: HashSet<u8>,
     scratch_numbers: HashSet<u8>,
 }
 
 impl Card {
-    fn new(line: &str) -> Self {
-        let (_, numbers) = line.splitn(2, ':').collect_tuple().unwrap();
+    fn parse(line: usize, text: &str) -> Result<Self, ParseError> {
+        let (_, numbers) = text
+            .split_once(':')
+            .ok_or_else(|| ParseError::at_end(line, text, "':' after card label"))?;
         let (w, s) = numbers
-            .splitn(2, '|')
-            .map(|num_list| {
-                num_list
-                    .split(' ')
-                    .filter_map(|num| num.trim().parse::<u8>().ok())
-                    .collect()
-            })
-            .collect_tuple()
-            .unwrap();
+            .split_once('|')
+            .ok_or_else(|| ParseError::at_end(line, text, "'|' between number lists"))?;
+        let parse_numbers = |num_list: &str| {
+            num_list
+                .split_whitespace()
+                .map(|num| parse_token::<u8>(line, text, num, "number below 256"))
+                .collect::<Result<HashSet<u8>, _>>()
+        };
 
-        Self {
-            winning_numbers: w,
-            scratch_numbers: s,
-        }
+        Ok(Self {
+            winning_numbers: parse_numbers(w)?,
+            scratch_numbers: parse_numbers(s)?,
+        })
     }
 
     fn match_count(&self) -> usize {
@@ -36,10 +39,8 @@ impl Card {
     }
 }
 
-fn run(input: PuzzleInput, part2: bool) -> u32 {
-    let match_counts = input
-        .filter(|line| !line.is_empty())
-        .map(|line| Card::new(&line).match_count());
+fn run(cards: &[Card], part2: bool) -> u32 {
+    let match_counts = cards.iter().map(|card| card.match_count());
 
     if part2 {
         match_counts
@@ -61,8 +62,38 @@ fn run(input: PuzzleInput, part2: bool) -> u32 {
     }
 }
 
-pub fn solution(input: PuzzleInput, part2: bool) -> String {
-    run(input, part2).to_string()
+/// Total points of all scratchcards
+pub fn part1(cards: &[Card]) -> u32 {
+    run(cards, false)
+}
+
+/// Number of scratchcards after winning copies of the following cards
+pub fn part2(cards: &[Card]) -> u32 {
+    run(cards, true)
+}
+
+pub struct Day4;
+
+impl Puzzle for Day4 {
+    type Input = Vec<Card>;
+
+    const TITLE: &'static str = "Scratchcards";
+
+    fn parse(input: PuzzleInput) -> Result<Self::Input, ParseError> {
+        input
+            .enumerate()
+            .filter(|(_, text)| !text.is_empty())
+            .map(|(line, text)| Card::parse(line, &text))
+            .collect()
+    }
+
+    fn part1(cards: &Self::Input, _params: &Params) -> Answer {
+        part1(cards).into()
+    }
+
+    fn part2(cards: &Self::Input, _params: &Params) -> Answer {
+        part2(cards).into()
+    }
 }
 
 #[cfg(test)]
@@ -77,7 +108,8 @@ Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1
 Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83
 Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
 Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11";
-        assert_eq!(run(test_input.into(), false), 13);
-        assert_eq!(run(test_input.into(), true), 30);
+        let cards = Day4::parse(test_input.into()).unwrap();
+        assert_eq!(run(&cards, false), 13);
+        assert_eq!(run(&cards, true), 30);
     }
 }
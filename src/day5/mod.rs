@@ -1,33 +1,49 @@
+//! Day 5: If You Give A Seed A Fertilizer
+//!
+//! The [`Almanac`] holds the seeds and a [`RangeMapping`] for each category conversion.
+
 use std::ops::Range;
 
 use itertools::Itertools;
 
-use crate::{range_intersect, PuzzleInput};
+use crate::{parse_token, range_intersect, Answer, Params, ParseError, Puzzle, PuzzleInput};
 
+/// Conversion of source to destination numbers, which keeps numbers outside of its ranges
 #[derive(Debug)]
-struct RangeMapping {
+pub struct RangeMapping {
     ranges: Vec<(Range<u64>, u64)>,
 }
 
 impl RangeMapping {
-    fn new<T>(lines: T) -> Self
+    fn parse<T>(lines: T) -> Result<Self, ParseError>
     where
-        T: IntoIterator<Item = String>,
+        T: IntoIterator<Item = (usize, String)>,
     {
-        Self {
+        Ok(Self {
             ranges: lines
                 .into_iter()
-                .filter_map(|line| {
-                    line.splitn(3, ' ')
-                        .filter_map(|n| n.parse().ok())
-                        .collect_tuple()
+                .filter(|(_, text)| !text.is_empty() && !text.ends_with("map:"))
+                .map(|(line, text)| {
+                    let nums: Vec<u64> = text
+                        .split_whitespace()
+                        .map(|n| parse_token(line, &text, n, "range number"))
+                        .try_collect()?;
+                    if let [destination, start, len] = nums[..] {
+                        Ok((start..start + len, destination))
+                    } else {
+                        Err(ParseError::new(
+                            line,
+                            0,
+                            "'<destination> <source> <length>' range",
+                        ))
+                    }
                 })
-                .map(|(destination, start, len)| (start..start + len, destination))
-                .collect_vec(),
-        }
+                .try_collect()?,
+        })
     }
 
-    fn transform(&self, range: Range<u64>) -> impl Iterator<Item = Range<u64>> {
+    /// Ranges which the numbers of `range` convert to
+    pub fn transform(&self, range: Range<u64>) -> impl Iterator<Item = Range<u64>> {
         self.ranges
             .iter()
             .filter_map(|(r, destination)| {
@@ -62,40 +78,42 @@ impl RangeMapping {
     }
 }
 
-fn run(mut input: PuzzleInput, part2: bool) -> u64 {
-    let seeds = {
-        // Parse seeds
-        let first_line = input.next().unwrap();
-        let (_, seeds_list) = first_line.splitn(2, ':').collect_tuple().unwrap();
-        let seed_nums = seeds_list
-            .split(' ')
-            .filter_map(|num| num.parse::<u64>().ok());
-
-        if part2 {
-            seed_nums
-                .chunks(2)
-                .into_iter()
-                .filter_map(|nums| nums.collect_tuple())
-                .map(|(start, len)| start..start + len)
-                .collect_vec()
-        } else {
-            seed_nums.map(|start| start..start + 1).collect_vec()
-        }
+pub struct Almanac {
+    seeds: Vec<u64>,
+    mappings: Vec<RangeMapping>,
+}
+
+impl Almanac {
+    pub fn seeds(&self) -> &[u64] {
+        &self.seeds
+    }
+
+    /// Mappings from seeds to locations in order of application
+    pub fn mappings(&self) -> &[RangeMapping] {
+        &self.mappings
+    }
+}
+
+fn run(almanac: &Almanac, part2: bool) -> u64 {
+    let seeds = if part2 {
+        almanac
+            .seeds
+            .iter()
+            .tuples()
+            .map(|(&start, &len)| start..start + len)
+            .collect_vec()
+    } else {
+        almanac
+            .seeds
+            .iter()
+            .map(|&start| start..start + 1)
+            .collect_vec()
     };
 
-    let locations = input
-        // Parse range mappings
-        .scan(0, |step, line| {
-            if line.ends_with("map:") {
-                *step += 1;
-            }
-            Some((*step, line))
-        })
-        .group_by(|(step, _)| *step)
-        .into_iter()
-        .filter(|(step, _)| *step > 0)
-        .map(|(_, lines)| RangeMapping::new(lines.map(|(_, l)| l)))
-        // Transform from "seeds" to "locations" by applying all mappings
+    // Transform from "seeds" to "locations" by applying all mappings
+    let locations = almanac
+        .mappings
+        .iter()
         .fold(seeds, |mapped_values, range_mapping| {
             mapped_values
                 .into_iter()
@@ -106,8 +124,61 @@ fn run(mut input: PuzzleInput, part2: bool) -> u64 {
     locations.into_iter().map(|r| r.start).min().unwrap()
 }
 
-pub fn solution(input: PuzzleInput, part2: bool) -> String {
-    run(input, part2).to_string()
+/// Lowest location of any seed
+pub fn part1(almanac: &Almanac) -> u64 {
+    run(almanac, false)
+}
+
+/// Lowest location of any seed within the seed ranges
+pub fn part2(almanac: &Almanac) -> u64 {
+    run(almanac, true)
+}
+
+pub struct Day5;
+
+impl Puzzle for Day5 {
+    type Input = Almanac;
+
+    const TITLE: &'static str = "If You Give A Seed A Fertilizer";
+
+    fn parse(mut input: PuzzleInput) -> Result<Self::Input, ParseError> {
+        let seeds = {
+            // Parse seeds
+            let first_line = input.next().unwrap_or_default();
+            let (_, seeds_list) = first_line
+                .split_once(':')
+                .ok_or_else(|| ParseError::at_end(0, &first_line, "':' after 'seeds'"))?;
+            seeds_list
+                .split_whitespace()
+                .map(|num| parse_token::<u64>(0, &first_line, num, "seed number"))
+                .try_collect()?
+        };
+
+        let mappings = input
+            // Parse range mappings
+            .enumerate()
+            .scan(0, |step, (line, text)| {
+                if text.ends_with("map:") {
+                    *step += 1;
+                }
+                Some((*step, (line + 1, text)))
+            })
+            .group_by(|(step, _)| *step)
+            .into_iter()
+            .filter(|(step, _)| *step > 0)
+            .map(|(_, lines)| RangeMapping::parse(lines.map(|(_, l)| l)))
+            .try_collect()?;
+
+        Ok(Almanac { seeds, mappings })
+    }
+
+    fn part1(almanac: &Self::Input, _params: &Params) -> Answer {
+        part1(almanac).into()
+    }
+
+    fn part2(almanac: &Self::Input, _params: &Params) -> Answer {
+        part2(almanac).into()
+    }
 }
 
 #[cfg(test)]
@@ -149,7 +220,8 @@ temperature-to-humidity map:
 humidity-to-location map:
 60 56 37
 56 93 4";
-        assert_eq!(run(test_input.into(), false), 35);
-        assert_eq!(run(test_input.into(), true), 46);
+        let almanac = Day5::parse(test_input.into()).unwrap();
+        assert_eq!(run(&almanac, false), 35);
+        assert_eq!(run(&almanac, true), 46);
     }
 }
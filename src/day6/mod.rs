@@ -1,8 +1,10 @@
-use std::ops::Range;
+//! Day 6: Wait For It
+//!
+//! The [`RaceSheet`] holds the duration and record distance of each race.
 
-use itertools::Itertools;
+use std::ops::Range;
 
-use crate::PuzzleInput;
+use crate::{parse_token, Answer, Params, ParseError, Puzzle, PuzzleInput};
 
 /// When pressing the button for t time units the velocity increases linearly: v(t) = t
 /// Distance(t) = v(t) * (race_duration - t)
@@ -18,25 +20,28 @@ fn record_beating_range(race_duration: f64, record_distance: f64) -> Option<Rang
     }
 }
 
-fn run(input: PuzzleInput, part2: bool) -> u64 {
-    let (time, distance) = input
-        .map(|line| {
-            let (_, num_list) = line.splitn(2, ':').collect_tuple().unwrap();
-            if part2 {
-                vec![num_list
-                    .split_whitespace()
-                    .collect::<String>()
-                    .parse::<u64>()
-                    .unwrap()]
-            } else {
-                num_list
-                    .split_whitespace()
-                    .map(|num| num.parse::<u64>().unwrap())
-                    .collect_vec()
-            }
-        })
-        .collect_tuple()
-        .unwrap();
+/// Race durations and record distances from the sheet of paper
+pub struct RaceSheet {
+    times: Vec<u64>,
+    distances: Vec<u64>,
+}
+
+/// Join numbers as if the spaces between them were removed: `[7, 15, 30] => 71530`
+fn concat_digits(nums: &[u64]) -> u64 {
+    nums.iter().fold(0, |joined, &num| {
+        joined * 10_u64.pow(num.max(1).ilog10() + 1) + num
+    })
+}
+
+fn run(sheet: &RaceSheet, part2: bool) -> u64 {
+    let (time, distance) = if part2 {
+        (
+            vec![concat_digits(&sheet.times)],
+            vec![concat_digits(&sheet.distances)],
+        )
+    } else {
+        (sheet.times.clone(), sheet.distances.clone())
+    };
     time.into_iter()
         .zip(distance)
         .flat_map(|(t, d)| record_beating_range(t as f64, d as f64))
@@ -45,8 +50,52 @@ fn run(input: PuzzleInput, part2: bool) -> u64 {
         .product()
 }
 
-pub fn solution(input: PuzzleInput, part2: bool) -> String {
-    run(input, part2).to_string()
+/// Product of the numbers of ways to beat the record of each race
+pub fn part1(sheet: &RaceSheet) -> u64 {
+    run(sheet, false)
+}
+
+/// Number of ways to beat the record of the single race with concatenated digits
+pub fn part2(sheet: &RaceSheet) -> u64 {
+    run(sheet, true)
+}
+
+pub struct Day6;
+
+impl Puzzle for Day6 {
+    type Input = RaceSheet;
+
+    const TITLE: &'static str = "Wait For It";
+
+    fn parse(input: PuzzleInput) -> Result<Self::Input, ParseError> {
+        let mut num_lists = input.enumerate().take(2).map(|(line, text)| {
+            let (_, num_list) = text
+                .split_once(':')
+                .ok_or_else(|| ParseError::at_end(line, &text, "':' after label"))?;
+            num_list
+                .split_whitespace()
+                .map(|num| parse_token::<u64>(line, &text, num, "number"))
+                .collect::<Result<Vec<_>, _>>()
+        });
+        let times = num_lists
+            .next()
+            .unwrap_or_else(|| Err(ParseError::new(0, 0, "'Time:' line")))?;
+        let distances = num_lists
+            .next()
+            .unwrap_or_else(|| Err(ParseError::new(1, 0, "'Distance:' line")))?;
+        if times.len() != distances.len() {
+            return Err(ParseError::new(1, 0, "a distance for each time"));
+        }
+        Ok(RaceSheet { times, distances })
+    }
+
+    fn part1(sheet: &Self::Input, _params: &Params) -> Answer {
+        part1(sheet).into()
+    }
+
+    fn part2(sheet: &Self::Input, _params: &Params) -> Answer {
+        part2(sheet).into()
+    }
 }
 
 #[cfg(test)]
@@ -57,7 +106,8 @@ mod test {
     fn test_run() {
         let test_input = r"Time:      7  15   30
 Distance:  9  40  200";
-        assert_eq!(run(test_input.into(), false), 288);
-        assert_eq!(run(test_input.into(), true), 71503);
+        let sheet = Day6::parse(test_input.into()).unwrap();
+        assert_eq!(run(&sheet, false), 288);
+        assert_eq!(run(&sheet, true), 71503);
     }
 }
@@ -1,9 +1,12 @@
+//! Day 7: Camel Cards
+//!
+//! Each [`Hand`] holds its cards and bid.
+
 use std::cmp::Ordering;
 
-use anyhow::{anyhow, Context, Result};
 use itertools::Itertools;
 
-use crate::PuzzleInput;
+use crate::{parse_token, Answer, Params, ParseError, Puzzle, PuzzleInput};
 
 const CARDS: usize = 5;
 
@@ -22,34 +25,47 @@ fn char_to_joke_strength(c: char) -> u8 {
 }
 
 #[derive(Debug, PartialEq, Eq)]
-struct Hand {
-    card_strengths: [u8; CARDS],
-    hand_type: u8,
+pub struct Hand {
+    cards: [char; CARDS],
     bet: u32,
 }
 
 impl Hand {
-    fn parse(line: &str, part2: bool) -> Result<Self> {
-        let (hand, bet) = line
-            .splitn(2, ' ')
-            .collect_tuple()
-            .ok_or(anyhow!("expect space"))?;
-        let bet = bet.parse::<u32>().with_context(|| "expect bet number")?;
-        let hand_chars = array_init::from_iter(hand.chars())
-            .ok_or_else(|| anyhow!("expect {CARDS} cards in hand"))?;
-
-        let card_strengths = hand_chars.map(if part2 {
+    fn parse(line: usize, text: &str) -> Result<Self, ParseError> {
+        let (hand, bet) = text
+            .split_once(' ')
+            .ok_or_else(|| ParseError::at_end(line, text, "space before bet"))?;
+        let bet = parse_token(line, text, bet, "bet number")?;
+        if let Some(col) = hand.chars().position(|c| !"23456789TJQKA".contains(c)) {
+            return Err(ParseError::new(line, col, "card label"));
+        }
+        let cards = Some(hand)
+            .filter(|hand| hand.len() == CARDS)
+            .and_then(|hand| array_init::from_iter(hand.chars()))
+            .ok_or_else(|| ParseError::new(line, 0, format!("{CARDS} cards in hand")))?;
+        Ok(Self { cards, bet })
+    }
+
+    fn strength(&self, part2: bool) -> HandStrength {
+        let card_strengths = self.cards.map(if part2 {
             char_to_joke_strength
         } else {
             char_to_strength
         });
-        Ok(Self {
+        HandStrength {
             card_strengths,
-            hand_type: Self::strengths_to_type(&card_strengths, part2),
-            bet,
-        })
+            hand_type: HandStrength::strengths_to_type(&card_strengths, part2),
+        }
     }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct HandStrength {
+    card_strengths: [u8; CARDS],
+    hand_type: u8,
+}
 
+impl HandStrength {
     fn strengths_to_type(card_strengths: &[u8; CARDS], joker_wildcard: bool) -> u8 {
         let mut strength_counts = [0_u8; 13];
         for strength in card_strengths {
@@ -75,13 +91,13 @@ impl Hand {
     }
 }
 
-impl PartialOrd for Hand {
+impl PartialOrd for HandStrength {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
         Some(self.cmp(other))
     }
 }
 
-impl Ord for Hand {
+impl Ord for HandStrength {
     fn cmp(&self, other: &Self) -> Ordering {
         self.hand_type.cmp(&other.hand_type).then_with(|| {
             self.card_strengths
@@ -94,19 +110,49 @@ impl Ord for Hand {
     }
 }
 
-fn run(input: PuzzleInput, part2: bool) -> u32 {
-    input
-        .filter(|line| !line.is_empty())
-        .map(|line| Hand::parse(&line, part2).unwrap())
-        .sorted()
+fn run(hands: &[Hand], part2: bool) -> u32 {
+    hands
+        .iter()
+        .map(|hand| (hand.strength(part2), hand.bet))
+        .sorted_by(|(a, _), (b, _)| a.cmp(b))
         .enumerate()
         //.inspect(|(rank, (hand, bet))| println!("{rank}: {hand:?} {bet}"))
-        .map(|(rank, hand)| (rank as u32 + 1) * hand.bet)
+        .map(|(rank, (_, bet))| (rank as u32 + 1) * bet)
         .sum()
 }
 
-pub fn solution(input: PuzzleInput, part2: bool) -> String {
-    run(input, part2).to_string()
+/// Total winnings of all hands ranked by strength
+pub fn part1(hands: &[Hand]) -> u32 {
+    run(hands, false)
+}
+
+/// Total winnings when `J` cards are jokers
+pub fn part2(hands: &[Hand]) -> u32 {
+    run(hands, true)
+}
+
+pub struct Day7;
+
+impl Puzzle for Day7 {
+    type Input = Vec<Hand>;
+
+    const TITLE: &'static str = "Camel Cards";
+
+    fn parse(input: PuzzleInput) -> Result<Self::Input, ParseError> {
+        input
+            .enumerate()
+            .filter(|(_, text)| !text.is_empty())
+            .map(|(line, text)| Hand::parse(line, &text))
+            .collect()
+    }
+
+    fn part1(hands: &Self::Input, _params: &Params) -> Answer {
+        part1(hands).into()
+    }
+
+    fn part2(hands: &Self::Input, _params: &Params) -> Answer {
+        part2(hands).into()
+    }
 }
 
 #[cfg(test)]
@@ -120,7 +166,8 @@ T55J5 684
 KK677 28
 KTJJT 220
 QQQJA 483";
-        assert_eq!(run(test_input.into(), false), 6440);
-        assert_eq!(run(test_input.into(), true), 5905);
+        let hands = Day7::parse(test_input.into()).unwrap();
+        assert_eq!(run(&hands, false), 6440);
+        assert_eq!(run(&hands, true), 5905);
     }
 }
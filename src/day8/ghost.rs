@@ -0,0 +1,36 @@
+use std::collections::HashMap;
+
+use crate::Recurrence;
+use bitvec::slice::BitSlice;
+
+use super::{
+    network::{Node, NodeNetwork},
+    walk,
+};
+
+/// Steps at which a ghost walking from `start_node` stands on an end node.
+/// The walk repeats once a (node, instruction index) state repeats.
+pub fn ghost_cycle(
+    network: &NodeNetwork,
+    start_node: Node,
+    go_right_instructions: &[bool],
+    end_nodes: &BitSlice,
+) -> Recurrence {
+    let instruction_count = go_right_instructions.len();
+    let mut first_visits: HashMap<(Node, usize), u64> = HashMap::new();
+    let mut ends = vec![];
+    let nodes = [start_node]
+        .into_iter()
+        .chain(walk(network, start_node, go_right_instructions));
+    for (step, node) in nodes.enumerate() {
+        let step_count = step as u64;
+        let state = (node, step % instruction_count);
+        if let Some(cycle_start) = first_visits.insert(state, step_count) {
+            return Recurrence::new(ends, cycle_start, step_count - cycle_start);
+        }
+        if end_nodes[node.index()] {
+            ends.push(step_count);
+        }
+    }
+    unreachable!("instructions repeat endlessly")
+}
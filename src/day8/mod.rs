@@ -1,15 +1,23 @@
+//! Day 8: Haunted Wasteland
+//!
+//! The [`Documents`] hold the left/right instructions and the node network;
+//! [`Endpoints`] select the start and end nodes of a walk.
+
 use itertools::Itertools;
 
-use crate::PuzzleInput;
+use crate::{first_common_step, Answer, Params, ParseError, Puzzle, PuzzleInput};
+mod ghost;
 mod network;
+use ghost::ghost_cycle;
+pub use network::LabelPattern;
 use network::{Node, NodeNetwork};
 
-pub fn steps_until_end(
-    network: &NodeNetwork,
+/// Nodes visited after each step when following the instructions from `start_node`
+fn walk<'a>(
+    network: &'a NodeNetwork,
     start_node: Node,
-    go_right_instructions: &[bool],
-    part2: bool,
-) -> usize {
+    go_right_instructions: &'a [bool],
+) -> impl Iterator<Item = Node> + 'a {
     go_right_instructions
         .iter()
         .copied()
@@ -19,38 +27,173 @@ pub fn steps_until_end(
             *pos = if go_right { right } else { left };
             Some(*pos)
         })
-        .position(|pos| pos.is_end_node(part2))
-        .unwrap()
-        + 1
 }
 
-fn run(mut input: PuzzleInput, part2: bool) -> u64 {
-    let go_right_instructions = input
-        .next()
-        .unwrap()
-        .chars()
-        .map(|c| c == 'R')
+/// Split the alphanumeric label at the start of `rest` off of it
+fn take_label<'a>(line: usize, text: &str, rest: &mut &'a str) -> Result<&'a str, ParseError> {
+    let length = rest
+        .find(|c: char| !c.is_ascii_alphanumeric())
+        .unwrap_or(rest.len());
+    if length == 0 {
+        return Err(ParseError::at(line, text, rest, "alphanumeric node label"));
+    }
+    let (label, tail) = rest.split_at(length);
+    *rest = tail;
+    Ok(label)
+}
+
+/// Strip `separator` from the start of `rest`
+fn take_separator(
+    line: usize,
+    text: &str,
+    rest: &mut &str,
+    separator: &str,
+) -> Result<(), ParseError> {
+    *rest = rest
+        .strip_prefix(separator)
+        .ok_or_else(|| ParseError::at(line, text, rest, format!("'{separator}'")))?;
+    Ok(())
+}
+
+/// Parse a `AAA = (BBB, CCC)` line into its three labels
+fn parse_node_line(line: usize, text: &str) -> Result<[&str; 3], ParseError> {
+    let mut rest = text;
+    let node = take_label(line, text, &mut rest)?;
+    take_separator(line, text, &mut rest, " = (")?;
+    let left = take_label(line, text, &mut rest)?;
+    take_separator(line, text, &mut rest, ", ")?;
+    let right = take_label(line, text, &mut rest)?;
+    take_separator(line, text, &mut rest, ")")?;
+    if !rest.is_empty() {
+        return Err(ParseError::at(line, text, rest, "end of line"));
+    }
+    Ok([node, left, right])
+}
+
+/// Labels of the nodes where walks start and end
+pub struct Endpoints {
+    pub start: LabelPattern,
+    pub end: LabelPattern,
+}
+
+impl Endpoints {
+    /// From `AAA` to `ZZZ`
+    pub fn part1() -> Self {
+        Self {
+            start: LabelPattern::Exact("AAA".into()),
+            end: LabelPattern::Exact("ZZZ".into()),
+        }
+    }
+
+    /// From every `..A` at once to `..Z` nodes
+    pub fn part2() -> Self {
+        Self {
+            start: LabelPattern::Suffix("A".into()),
+            end: LabelPattern::Suffix("Z".into()),
+        }
+    }
+}
+
+/// Left/right instructions and the network of nodes to navigate
+pub struct Documents {
+    go_right_instructions: Vec<bool>,
+    network: NodeNetwork,
+}
+
+/// Steps until the walks from all start nodes stand on end nodes at once,
+/// if they ever do
+pub fn run(documents: &Documents, endpoints: &Endpoints) -> Option<u64> {
+    let network = &documents.network;
+    let instructions = &documents.go_right_instructions;
+    let end_nodes = network.node_set(&endpoints.end);
+    // Each ghost eventually repeats a (node, instruction index) state.
+    // Its end nodes before and within that cycle give the steps it can end on,
+    // which are combined for all ghosts by the Chinese remainder theorem.
+    let ghosts = network
+        .nodes(&endpoints.start)
+        .map(|n| ghost_cycle(network, n, instructions, &end_nodes))
         .collect_vec();
-    let network: NodeNetwork = input
-        .filter(|line| !line.is_empty())
-        .map(|line| [&line[0..3], &line[7..10], &line[12..15]].map(|v| v.into()))
-        .collect();
-
-    // Part2: Each ghost is starting at "??A" (??A = (ABC, DEF)
-    // and reaches "??E" (??Z = (DEF, ABC)) after `n` steps.
-    //
-    // This is not mentioned in the puzzle description but is required for correctness:
-    // When starting at an ending, no other endings can be reached
-    // and it again takes `n` steps to cycle back to the same ending.
-    network
-        .start_nodes(part2)
-        .map(|n| steps_until_end(&network, n, &go_right_instructions, part2) as u64)
-        .reduce(num_integer::lcm)
-        .unwrap()
+    first_common_step(&ghosts, 1)
+}
+
+/// Steps from `AAA` to `ZZZ`
+pub fn part1(documents: &Documents) -> Option<u64> {
+    run(documents, &Endpoints::part1())
+}
+
+/// Steps until all ghosts starting on nodes ending with `A` stand on nodes ending with `Z`
+pub fn part2(documents: &Documents) -> Option<u64> {
+    run(documents, &Endpoints::part2())
 }
 
-pub fn solution(input: PuzzleInput, part2: bool) -> String {
-    run(input, part2).to_string()
+/// Steps of a part, or why there are none
+fn steps_answer(steps: Option<u64>) -> Answer {
+    steps.map_or_else(
+        || Answer::Unsolved("start nodes never stand on end nodes at once".into()),
+        Answer::from,
+    )
+}
+
+pub struct Day8;
+
+impl Puzzle for Day8 {
+    type Input = Documents;
+
+    const TITLE: &'static str = "Haunted Wasteland";
+
+    fn parse(mut input: PuzzleInput) -> Result<Self::Input, ParseError> {
+        let first_line = input.next().unwrap_or_default();
+        let go_right_instructions: Vec<bool> = first_line
+            .char_indices()
+            .map(|(col, c)| match c {
+                'L' => Ok(false),
+                'R' => Ok(true),
+                _ => Err(ParseError::new(0, col, "'L' or 'R' instruction")),
+            })
+            .try_collect()?;
+        if go_right_instructions.is_empty() {
+            return Err(ParseError::new(0, 0, "'L' or 'R' instruction"));
+        }
+        let lines = input
+            .enumerate()
+            .filter(|(_, text)| !text.is_empty())
+            .collect_vec();
+        let definitions: Vec<[&str; 3]> = lines
+            .iter()
+            .map(|(line, text)| parse_node_line(line + 1, text))
+            .try_collect()?;
+        let network = NodeNetwork::new(&definitions).map_err(|undefined| {
+            // `undefined` is a slice of the line which references it
+            let ((line, text), token) = lines
+                .iter()
+                .zip(&definitions)
+                .find_map(|(line, [_, left, right])| {
+                    [left, right]
+                        .into_iter()
+                        .find(|token| token.as_ptr() == undefined.as_ptr())
+                        .map(|token| (line, *token))
+                })
+                .unwrap();
+            ParseError::at(
+                line + 1,
+                text,
+                token,
+                format!("node '{undefined}' to be defined"),
+            )
+        })?;
+        Ok(Documents {
+            go_right_instructions,
+            network,
+        })
+    }
+
+    fn part1(documents: &Self::Input, _params: &Params) -> Answer {
+        steps_answer(part1(documents))
+    }
+
+    fn part2(documents: &Self::Input, _params: &Params) -> Answer {
+        steps_answer(part2(documents))
+    }
 }
 
 #[cfg(test)]
@@ -69,7 +212,13 @@ EEE = (EEE, EEE)
 GGG = (GGG, GGG)
 ZZZ = (ZZZ, ZZZ)";
 
-        assert_eq!(run(test_input.into(), false), 2);
+        assert_eq!(
+            run(
+                &Day8::parse(test_input.into()).unwrap(),
+                &Endpoints::part1()
+            ),
+            Some(2)
+        );
     }
 
     #[test]
@@ -79,7 +228,13 @@ ZZZ = (ZZZ, ZZZ)";
 AAA = (BBB, BBB)
 BBB = (AAA, ZZZ)
 ZZZ = (ZZZ, ZZZ)";
-        assert_eq!(run(test_input2.into(), false), 6);
+        assert_eq!(
+            run(
+                &Day8::parse(test_input2.into()).unwrap(),
+                &Endpoints::part1()
+            ),
+            Some(6)
+        );
     }
 
     #[test]
@@ -94,6 +249,103 @@ MMB = (MMC, MMC)
 MMC = (MMZ, MMZ)
 MMZ = (MMB, MMB)
 XXX = (XXX, XXX)";
-        assert_eq!(run(test_input3.into(), true), 6);
+        assert_eq!(
+            run(
+                &Day8::parse(test_input3.into()).unwrap(),
+                &Endpoints::part2()
+            ),
+            Some(6)
+        );
+    }
+
+    #[test]
+    fn test_run_for_part2_offset_cycles() {
+        // `AAA` ends after 2, 5, 8, ... steps and `BBA` after 1, 3, 5, ... steps
+        // so that the first common end is not the LCM of the first ends
+        let test_input = r"L
+
+AAA = (AAB, XXX)
+AAB = (AAZ, XXX)
+AAZ = (AAC, XXX)
+AAC = (AAD, XXX)
+AAD = (AAZ, XXX)
+BBA = (BBZ, XXX)
+BBZ = (BBB, XXX)
+BBB = (BBZ, XXX)
+XXX = (XXX, XXX)";
+        assert_eq!(
+            run(
+                &Day8::parse(test_input.into()).unwrap(),
+                &Endpoints::part2()
+            ),
+            Some(5)
+        );
+    }
+
+    #[test]
+    fn test_run_alphanumeric_labels() {
+        let test_input = r"LR
+
+11A = (11B, XXX)
+11B = (XXX, 11Z)
+11Z = (11B, XXX)
+22A = (22B, XXX)
+22B = (22C, 22C)
+22C = (22Z, 22Z)
+22Z = (22B, 22B)
+XXX = (XXX, XXX)";
+        assert_eq!(
+            run(
+                &Day8::parse(test_input.into()).unwrap(),
+                &Endpoints::part2()
+            ),
+            Some(6)
+        );
+        let test_input = r"R
+
+START = (START, a1)
+a1 = (START, END)
+END = (END, END)";
+        let endpoints = Endpoints {
+            start: LabelPattern::Exact("START".into()),
+            end: LabelPattern::Exact("END".into()),
+        };
+        assert_eq!(
+            run(&Day8::parse(test_input.into()).unwrap(), &endpoints),
+            Some(2)
+        );
+    }
+
+    #[test]
+    fn test_run_unsolved() {
+        // `ZZZ` is unreachable from `AAA`, and no node ends with `B`
+        let documents = Day8::parse("L\n\nAAA = (AAA, ZZZ)\nZZZ = (ZZZ, ZZZ)".into()).unwrap();
+        assert_eq!(run(&documents, &Endpoints::part1()), None);
+        let endpoints = Endpoints {
+            start: LabelPattern::Suffix("B".into()),
+            end: LabelPattern::Suffix("Z".into()),
+        };
+        assert_eq!(run(&documents, &endpoints), None);
+        // Ghosts end on even and odd steps only
+        let documents = Day8::parse(
+            "L\n\nAAA = (AAZ, AAA)\nAAZ = (AAA, AAA)\nBBA = (BBB, BBB)\nBBB = (BBZ, BBZ)\nBBZ = (BBB, BBB)"
+                .into(),
+        )
+        .unwrap();
+        assert_eq!(run(&documents, &Endpoints::part2()), None);
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let err = Day8::parse("LR\n\nAAA = (BBB, CCC)\nBBB = (AAA; CCC)".into())
+            .err()
+            .unwrap();
+        assert_eq!(err, ParseError::new(3, 10, "', '"));
+        let err = Day8::parse("LR\n\nAAA = (BBB, CCC)\nBBB = (AAA, AAA)".into())
+            .err()
+            .unwrap();
+        assert_eq!(err, ParseError::new(2, 12, "node 'CCC' to be defined"));
+        let err = Day8::parse("\n\nAAA = (AAA, AAA)".into()).err().unwrap();
+        assert_eq!(err, ParseError::new(0, 0, "'L' or 'R' instruction"));
     }
 }
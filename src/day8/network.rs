@@ -1,61 +1,79 @@
-use std::{fmt::Display, ops::Index};
+use std::{collections::HashMap, ops::Index};
 
-use either::Either;
+use bitvec::prelude::*;
 
-const NODE_COUNT: usize = 26 * 26 * 26;
-const EMPTY_NODE: Node = Node(u16::MAX);
-
-#[derive(Debug, PartialEq, Eq, Clone, Copy)]
-pub struct Node(u16);
-
-impl From<&str> for Node {
-    fn from(value: &str) -> Self {
-        Self(
-            value
-                .chars()
-                .fold(0_u16, |identity, c| identity * 26 + (c as u16 - 'A' as u16)),
-        )
-    }
-}
-
-impl Display for Node {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let mid = self.0 / 26;
-        let first = mid / 26;
-        let chars = [first, mid, self.0].map(|n| (b'A' + (n % 26) as u8) as char);
-        write!(f, "{}", chars.into_iter().collect::<String>())
-    }
-}
+/// Interned node label
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub struct Node(u32);
 
 impl Node {
     pub fn index(&self) -> usize {
         self.0 as usize
     }
+}
 
-    pub fn is_end_node(&self, part2: bool) -> bool {
-        if part2 {
-            self.0 % 26 == (b'Z' - b'A') as u16
-        } else {
-            self.0 == Into::<Self>::into("ZZZ").0
-        }
-    }
+/// Selection of nodes by their label
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LabelPattern {
+    Exact(String),
+    Suffix(String),
+}
 
-    pub fn start_nodes(part2: bool) -> impl Iterator<Item = Self> {
-        if part2 {
-            Either::Left((0..NODE_COUNT / 26).map(|i| Self(i as u16 * 26)))
-        } else {
-            Either::Right(["AAA".into()].into_iter())
+impl LabelPattern {
+    pub fn matches(&self, label: &str) -> bool {
+        match self {
+            LabelPattern::Exact(exact) => label == exact,
+            LabelPattern::Suffix(suffix) => label.ends_with(suffix.as_str()),
         }
     }
 }
 
 /// Node collection which maps `start_node -> (left_node, right_node)`
-/// (This is a fixed sized array which can be initialized "faster" than a HashMap)
-pub struct NodeNetwork([(Node, Node); NODE_COUNT]);
+/// (Labels are interned to dense indices so that walks only index into arrays)
+pub struct NodeNetwork {
+    labels: Vec<String>,
+    edges: Vec<(Node, Node)>,
+}
 
 impl NodeNetwork {
-    pub fn start_nodes(&self, part2: bool) -> impl Iterator<Item = Node> + '_ {
-        Node::start_nodes(part2).filter(|n| self.0[n.index()].0 != EMPTY_NODE)
+    /// Network of `[node, left, right]` label definitions.
+    /// Returns the first left or right label which has no definition as error.
+    pub fn new<'a>(definitions: &[[&'a str; 3]]) -> Result<Self, &'a str> {
+        let indices: HashMap<&str, Node> = definitions
+            .iter()
+            .enumerate()
+            .map(|(index, [label, _, _])| (*label, Node(index as u32)))
+            .collect();
+        let node = |label: &'a str| indices.get(label).copied().ok_or(label);
+        let edges = definitions
+            .iter()
+            .map(|[_, left, right]| Ok((node(left)?, node(right)?)))
+            .collect::<Result<_, &str>>()?;
+        Ok(Self {
+            labels: definitions
+                .iter()
+                .map(|[label, _, _]| label.to_string())
+                .collect(),
+            edges,
+        })
+    }
+
+    /// Nodes with labels matching `pattern` in order of definition
+    pub fn nodes(&self, pattern: &LabelPattern) -> impl Iterator<Item = Node> + '_ {
+        let pattern = pattern.clone();
+        self.labels
+            .iter()
+            .enumerate()
+            .filter(move |(_, label)| pattern.matches(label))
+            .map(|(index, _)| Node(index as u32))
+    }
+
+    /// Bit set of the nodes with labels matching `pattern`
+    pub fn node_set(&self, pattern: &LabelPattern) -> BitVec {
+        self.labels
+            .iter()
+            .map(|label| pattern.matches(label))
+            .collect()
     }
 }
 
@@ -63,17 +81,7 @@ impl Index<Node> for NodeNetwork {
     type Output = (Node, Node);
 
     fn index(&self, index: Node) -> &Self::Output {
-        &self.0[index.index()]
-    }
-}
-
-impl FromIterator<[Node; 3]> for NodeNetwork {
-    fn from_iter<T: IntoIterator<Item = [Node; 3]>>(iter: T) -> Self {
-        let mut network = [(EMPTY_NODE, EMPTY_NODE); NODE_COUNT];
-        for [start, left, right] in iter.into_iter() {
-            network[start.index()] = (left, right);
-        }
-        Self(network)
+        &self.edges[index.index()]
     }
 }
 
@@ -83,28 +91,33 @@ mod test {
 
     #[test]
     fn test_node() {
-        for start_node in Node::start_nodes(false) {
-            assert!(start_node.to_string().ends_with('A'));
-        }
-
-        let end_node: Node = "ZZZ".into();
-        assert!(end_node.is_end_node(false));
-        assert!(end_node.is_end_node(true));
-        let end_node2: Node = "BBZ".into();
-        assert!(!end_node2.is_end_node(false));
-        assert!(end_node2.is_end_node(true));
-
-        for no_end_node in ["ZBB", "BZB", "ZBB"] {
-            let n: Node = no_end_node.into();
-            assert!(!n.is_end_node(false));
-            assert!(!n.is_end_node(true));
-        }
+        let network = NodeNetwork::new(&[
+            ["11A", "11B", "XXX"],
+            ["11B", "XXX", "11Z"],
+            ["XXX", "XXX", "XXX"],
+            ["11Z", "11B", "XXX"],
+        ])
+        .unwrap();
+        let start_nodes = network
+            .nodes(&LabelPattern::Suffix("A".into()))
+            .collect::<Vec<_>>();
+        assert_eq!(start_nodes, [Node(0)]);
+        let end_nodes = network.node_set(&LabelPattern::Exact("11Z".into()));
+        assert_eq!(end_nodes.iter_ones().collect::<Vec<_>>(), [3]);
+        assert!(!LabelPattern::Exact("ZZZ".into()).matches("BBZ"));
+        assert!(LabelPattern::Suffix("Z".into()).matches("BBZ"));
     }
 
     #[test]
-    fn test_find_next_end() {
-        let nodes: [Node; 3] = ["AAA", "BBB", "CCC"].map(|v| v.into());
-        let network: NodeNetwork = [nodes].into_iter().collect();
-        assert_eq!(network[nodes[0]], (nodes[1], nodes[2]));
+    fn test_edges() {
+        let network =
+            NodeNetwork::new(&[["AAAA", "BB", "C1"], ["BB", "BB", "BB"], ["C1", "C1", "C1"]])
+                .unwrap();
+        let (left, right) = network[Node(0)];
+        assert_eq!((left, right), (Node(1), Node(2)));
+        assert_eq!(
+            NodeNetwork::new(&[["AAA", "AAA", "BBB"]]).err(),
+            Some("BBB")
+        );
     }
 }
@@ -1,9 +1,13 @@
+//! Day 9: Mirage Maintenance
+//!
+//! Each report line is parsed into a sequence of values.
+
 use either::Either;
 use itertools::Itertools;
 
-use crate::PuzzleInput;
+use crate::{parse_token, Answer, Params, ParseError, Puzzle, PuzzleInput};
 
-fn next_num_in_sequence(sequence: &[i32], part2: bool) -> i32 {
+fn next_num_in_sequence(sequence: &[i32], part2: bool) -> i128 {
     let n = sequence.len();
     // Example: n = 4
     // x = (4 * a_4) - (6 * a_3) + (4 * a_2) + (a_1)
@@ -16,27 +20,56 @@ fn next_num_in_sequence(sequence: &[i32], part2: bool) -> i32 {
     .map(|(km1, val)| {
         (
             if km1 % 2 == 0 { 1 } else { -1 },
-            num_integer::binomial(n, km1 + 1) as i32,
+            num_integer::binomial(n as i128, km1 as i128 + 1),
             val,
         )
     })
-    .map(|(sign, coeff, val)| sign * coeff * val)
+    .map(|(sign, coeff, val)| sign * coeff * *val as i128)
     .sum()
 }
 
-fn run(input: PuzzleInput, part2: bool) -> i32 {
-    input
-        .map(|line| {
-            line.split_whitespace()
-                .map(|n| n.parse::<i32>().unwrap())
-                .collect_vec()
-        })
-        .map(|sequence| next_num_in_sequence(&sequence, part2))
+fn run(sequences: &[Vec<i32>], part2: bool) -> i128 {
+    sequences
+        .iter()
+        .map(|sequence| next_num_in_sequence(sequence, part2))
         .sum()
 }
 
-pub fn solution(input: PuzzleInput, part2: bool) -> String {
-    run(input, part2).to_string()
+/// Sum of the extrapolated next values of all sequences
+pub fn part1(sequences: &[Vec<i32>]) -> i128 {
+    run(sequences, false)
+}
+
+/// Sum of the extrapolated previous values of all sequences
+pub fn part2(sequences: &[Vec<i32>]) -> i128 {
+    run(sequences, true)
+}
+
+pub struct Day9;
+
+impl Puzzle for Day9 {
+    type Input = Vec<Vec<i32>>;
+
+    const TITLE: &'static str = "Mirage Maintenance";
+
+    fn parse(input: PuzzleInput) -> Result<Self::Input, ParseError> {
+        input
+            .enumerate()
+            .map(|(line, text)| {
+                text.split_whitespace()
+                    .map(|n| parse_token(line, &text, n, "sequence number"))
+                    .try_collect()
+            })
+            .collect()
+    }
+
+    fn part1(sequences: &Self::Input, _params: &Params) -> Answer {
+        part1(sequences).into()
+    }
+
+    fn part2(sequences: &Self::Input, _params: &Params) -> Answer {
+        part2(sequences).into()
+    }
 }
 
 #[cfg(test)]
@@ -48,7 +81,8 @@ mod test {
         let test_input = r"0 3 6 9 12 15
 1 3 6 10 15 21
 10 13 16 21 30 45";
-        assert_eq!(run(test_input.into(), false), 114);
-        assert_eq!(run(test_input.into(), true), 2);
+        let sequences = Day9::parse(test_input.into()).unwrap();
+        assert_eq!(run(&sequences, false), 114);
+        assert_eq!(run(&sequences, true), 2);
     }
 }
@@ -0,0 +1,103 @@
+use std::{error::Error, fmt::Display, str::FromStr};
+
+/// Unexpected puzzle input at a (zero-based) line and column
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub expected: String,
+}
+
+impl ParseError {
+    pub fn new(line: usize, column: usize, expected: impl Into<String>) -> Self {
+        Self {
+            line,
+            column,
+            expected: expected.into(),
+        }
+    }
+
+    /// Error located at `token` which must be a slice of `text`
+    pub fn at(line: usize, text: &str, token: &str, expected: impl Into<String>) -> Self {
+        let column = (token.as_ptr() as usize).saturating_sub(text.as_ptr() as usize);
+        Self::new(line, column.min(text.len()), expected)
+    }
+
+    /// Error located at the end of `text`
+    pub fn at_end(line: usize, text: &str, expected: impl Into<String>) -> Self {
+        Self::new(line, text.len(), expected)
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: expected {}",
+            self.line + 1,
+            self.column + 1,
+            self.expected
+        )
+    }
+}
+
+impl Error for ParseError {}
+
+/// Parse `token` which must be a slice of `text`
+pub fn parse_token<T: FromStr>(
+    line: usize,
+    text: &str,
+    token: &str,
+    expected: &str,
+) -> Result<T, ParseError> {
+    token
+        .parse()
+        .map_err(|_| ParseError::at(line, text, token, expected))
+}
+
+/// Check that a grid row has `width` characters which are all in `symbols`
+pub fn check_grid_row(
+    line: usize,
+    text: &str,
+    width: usize,
+    symbols: &str,
+) -> Result<(), ParseError> {
+    if let Some((col, _)) = text.char_indices().find(|(_, c)| !symbols.contains(*c)) {
+        Err(ParseError::new(line, col, format!("one of \"{symbols}\"")))
+    } else if text.len() != width {
+        Err(ParseError::new(
+            line,
+            text.len().min(width),
+            format!("row of width {width}"),
+        ))
+    } else {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_token() {
+        let text = "Game 12: 3 blue";
+        assert_eq!(parse_token::<u32>(0, text, &text[5..7], "game id"), Ok(12));
+        let err = parse_token::<u32>(2, text, &text[11..15], "cube count").unwrap_err();
+        assert_eq!(err, ParseError::new(2, 11, "cube count"));
+        assert_eq!(err.to_string(), "line 3, column 12: expected cube count");
+    }
+
+    #[test]
+    fn test_check_grid_row() {
+        assert_eq!(check_grid_row(0, ".#.", 3, ".#"), Ok(()));
+        assert_eq!(
+            check_grid_row(1, ".#x", 3, ".#"),
+            Err(ParseError::new(1, 2, "one of \".#\""))
+        );
+        assert_eq!(
+            check_grid_row(2, ".#", 3, ".#"),
+            Err(ParseError::new(2, 2, "row of width 3"))
+        );
+    }
+}
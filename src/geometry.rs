@@ -0,0 +1,253 @@
+use std::ops::{Add, AddAssign, Mul, Neg, Sub};
+
+/// Grid direction with rows growing downwards.
+/// Ordered counter-clockwise starting to the right, matching `Direction::index`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Direction {
+    Right,
+    Up,
+    Left,
+    Down,
+}
+
+impl Direction {
+    pub const ALL: [Direction; 4] = [
+        Direction::Right,
+        Direction::Up,
+        Direction::Left,
+        Direction::Down,
+    ];
+
+    /// Index of the direction in `Direction::ALL`
+    pub fn index(self) -> usize {
+        self as usize
+    }
+
+    /// Direction after a quarter turn counter-clockwise
+    pub fn turn_left(self) -> Self {
+        Self::ALL[(self.index() + 1) % 4]
+    }
+
+    /// Direction after a quarter turn clockwise
+    pub fn turn_right(self) -> Self {
+        Self::ALL[(self.index() + 3) % 4]
+    }
+
+    pub fn reverse(self) -> Self {
+        Self::ALL[(self.index() + 2) % 4]
+    }
+
+    pub fn is_vertical(self) -> bool {
+        matches!(self, Direction::Up | Direction::Down)
+    }
+
+    pub fn is_horizontal(self) -> bool {
+        !self.is_vertical()
+    }
+
+    /// Directions which do not turn back
+    pub fn ahead(self) -> impl Iterator<Item = Direction> {
+        let back = self.reverse();
+        Self::ALL.into_iter().filter(move |d| *d != back)
+    }
+
+    /// Direction after bouncing off a `/` mirror
+    pub fn reflect_slash(self) -> Self {
+        if self.is_vertical() {
+            self.turn_right()
+        } else {
+            self.turn_left()
+        }
+    }
+
+    /// Direction after bouncing off a `\` mirror
+    pub fn reflect_backslash(self) -> Self {
+        self.reflect_slash().reverse()
+    }
+
+    /// Offset of a single step
+    pub fn vector(self) -> Vector {
+        match self {
+            Direction::Right => Vector::new(0, 1),
+            Direction::Up => Vector::new(-1, 0),
+            Direction::Left => Vector::new(0, -1),
+            Direction::Down => Vector::new(1, 0),
+        }
+    }
+
+    /// Direction of a dig plan letter: `R`, `U`, `L` or `D`
+    pub fn from_letter(c: char) -> Option<Self> {
+        match c {
+            'R' => Some(Direction::Right),
+            'U' => Some(Direction::Up),
+            'L' => Some(Direction::Left),
+            'D' => Some(Direction::Down),
+            _ => None,
+        }
+    }
+
+    /// Direction of an arrow: `>`, `^`, `<` or `v`
+    pub fn from_arrow(c: char) -> Option<Self> {
+        match c {
+            '>' => Some(Direction::Right),
+            '^' => Some(Direction::Up),
+            '<' => Some(Direction::Left),
+            'v' => Some(Direction::Down),
+            _ => None,
+        }
+    }
+
+    /// Arrow pointing in this direction, see [`Direction::from_arrow`]
+    pub fn arrow(self) -> char {
+        match self {
+            Direction::Right => '>',
+            Direction::Up => '^',
+            Direction::Left => '<',
+            Direction::Down => 'v',
+        }
+    }
+
+    /// Both ends of a pipe glyph: `|`, `-`, `L`, `J`, `7` or `F`
+    pub fn pipe_ends(c: char) -> Option<[Self; 2]> {
+        match c {
+            '|' => Some([Direction::Up, Direction::Down]),
+            '-' => Some([Direction::Right, Direction::Left]),
+            'L' => Some([Direction::Right, Direction::Up]),
+            'J' => Some([Direction::Up, Direction::Left]),
+            '7' => Some([Direction::Left, Direction::Down]),
+            'F' => Some([Direction::Right, Direction::Down]),
+            _ => None,
+        }
+    }
+}
+
+/// Row and column displacement
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Vector {
+    pub row: isize,
+    pub col: isize,
+}
+
+impl Vector {
+    pub const fn new(row: isize, col: isize) -> Self {
+        Self { row, col }
+    }
+
+    /// Number of orthogonal steps
+    pub fn manhattan(self) -> usize {
+        self.row.unsigned_abs() + self.col.unsigned_abs()
+    }
+}
+
+impl Add for Vector {
+    type Output = Vector;
+
+    fn add(self, rhs: Vector) -> Vector {
+        Vector::new(self.row + rhs.row, self.col + rhs.col)
+    }
+}
+
+impl Sub for Vector {
+    type Output = Vector;
+
+    fn sub(self, rhs: Vector) -> Vector {
+        self + -rhs
+    }
+}
+
+impl Neg for Vector {
+    type Output = Vector;
+
+    fn neg(self) -> Vector {
+        Vector::new(-self.row, -self.col)
+    }
+}
+
+impl Mul<isize> for Vector {
+    type Output = Vector;
+
+    fn mul(self, rhs: isize) -> Vector {
+        Vector::new(self.row * rhs, self.col * rhs)
+    }
+}
+
+/// Unbounded row and column coordinate
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct Point {
+    pub row: isize,
+    pub col: isize,
+}
+
+impl Point {
+    pub const fn new(row: isize, col: isize) -> Self {
+        Self { row, col }
+    }
+}
+
+impl Add<Vector> for Point {
+    type Output = Point;
+
+    fn add(self, rhs: Vector) -> Point {
+        Point::new(self.row + rhs.row, self.col + rhs.col)
+    }
+}
+
+impl AddAssign<Vector> for Point {
+    fn add_assign(&mut self, rhs: Vector) {
+        *self = *self + rhs;
+    }
+}
+
+impl Sub for Point {
+    type Output = Vector;
+
+    fn sub(self, rhs: Point) -> Vector {
+        Vector::new(self.row - rhs.row, self.col - rhs.col)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_turn() {
+        for d in Direction::ALL {
+            assert_eq!(d.turn_left().turn_right(), d);
+            assert_eq!(d.turn_left().turn_left(), d.reverse());
+            assert_eq!(d.vector() + d.reverse().vector(), Vector::default());
+            assert_eq!(d.ahead().count(), 3);
+        }
+        assert_eq!(Direction::Right.turn_left(), Direction::Up);
+        assert_eq!(Direction::Up.turn_right(), Direction::Right);
+    }
+
+    #[test]
+    fn test_reflect() {
+        assert_eq!(Direction::Right.reflect_slash(), Direction::Up);
+        assert_eq!(Direction::Up.reflect_slash(), Direction::Right);
+        assert_eq!(Direction::Left.reflect_slash(), Direction::Down);
+        assert_eq!(Direction::Down.reflect_slash(), Direction::Left);
+
+        assert_eq!(Direction::Right.reflect_backslash(), Direction::Down);
+        assert_eq!(Direction::Up.reflect_backslash(), Direction::Left);
+        assert_eq!(Direction::Left.reflect_backslash(), Direction::Up);
+        assert_eq!(Direction::Down.reflect_backslash(), Direction::Right);
+    }
+
+    #[test]
+    fn test_point() {
+        let mut p = Point::new(2, 3);
+        p += Direction::Up.vector() * 2;
+        assert_eq!(p, Point::new(0, 3));
+        assert_eq!((p - Point::new(2, 0)).manhattan(), 5);
+        assert_eq!(Direction::from_letter('L'), Direction::from_arrow('<'));
+        for d in Direction::ALL {
+            assert_eq!(Direction::from_arrow(d.arrow()), Some(d));
+        }
+        assert_eq!(
+            Direction::pipe_ends('F'),
+            Some([Direction::Right, Direction::Down])
+        );
+    }
+}
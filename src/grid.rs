@@ -0,0 +1,380 @@
+use std::{
+    fmt::Display,
+    ops::{Index, IndexMut},
+};
+
+use bitvec::prelude::*;
+
+use crate::{check_grid_row, Direction, ParseError, Point, Vector};
+
+/// Row-major index of a grid cell
+pub type Position = usize;
+
+/// Dimensions of a row-major grid with row/column conversion and bounds-checked stepping
+pub trait GridShape {
+    fn width(&self) -> usize;
+
+    fn height(&self) -> usize;
+
+    fn len(&self) -> usize {
+        self.width() * self.height()
+    }
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn position(&self, row: usize, col: usize) -> Position {
+        row * self.width() + col
+    }
+
+    fn row_col(&self, position: Position) -> (usize, usize) {
+        num_integer::div_rem(position, self.width())
+    }
+
+    /// Unbounded coordinate of `position`
+    fn point(&self, position: Position) -> Point {
+        let (row, col) = self.row_col(position);
+        Point::new(row as isize, col as isize)
+    }
+
+    /// Position of `point`, if inside the grid
+    fn position_of(&self, point: Point) -> Option<Position> {
+        let row = usize::try_from(point.row)
+            .ok()
+            .filter(|&r| r < self.height())?;
+        let col = usize::try_from(point.col)
+            .ok()
+            .filter(|&c| c < self.width())?;
+        Some(self.position(row, col))
+    }
+
+    /// Position displaced by `vector`, if inside the grid
+    fn offset(&self, position: Position, vector: Vector) -> Option<Position> {
+        self.position_of(self.point(position) + vector)
+    }
+
+    /// Neighbouring position in `direction`, if inside the grid
+    fn step(&self, position: Position, direction: Direction) -> Option<Position> {
+        self.offset(position, direction.vector())
+    }
+
+    /// Positions right, up, left and down of `position` which are inside the grid
+    fn neighbours(&self, position: Position) -> impl Iterator<Item = Position> + '_
+    where
+        Self: Sized,
+    {
+        Direction::ALL
+            .into_iter()
+            .filter_map(move |d| self.step(position, d))
+    }
+}
+
+/// Row-major grid of cells
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    cells: Vec<T>,
+    width: usize,
+    height: usize,
+}
+
+impl<T> GridShape for Grid<T> {
+    fn width(&self) -> usize {
+        self.width
+    }
+
+    fn height(&self) -> usize {
+        self.height
+    }
+}
+
+impl<T> Grid<T> {
+    pub fn new(width: usize, height: usize, cells: Vec<T>) -> Self {
+        assert_eq!(
+            cells.len(),
+            width * height,
+            "grid cells should fill all rows"
+        );
+        Self {
+            cells,
+            width,
+            height,
+        }
+    }
+
+    pub fn filled(width: usize, height: usize, value: T) -> Self
+    where
+        T: Clone,
+    {
+        Self::new(width, height, vec![value; width * height])
+    }
+
+    /// Parse the non-empty `lines` of a grid with characters in `symbols`
+    pub fn parse(
+        lines: impl Iterator<Item = (usize, String)>,
+        symbols: &str,
+        mut cell: impl FnMut(char) -> T,
+    ) -> Result<Self, ParseError> {
+        let mut cells = vec![];
+        let mut width = None;
+        let mut height = 0;
+        for (line, text) in lines.filter(|(_, text)| !text.is_empty()) {
+            let width = *width.get_or_insert(text.chars().count());
+            check_grid_row(line, &text, width, symbols)?;
+            cells.extend(text.chars().map(&mut cell));
+            height += 1;
+        }
+        Ok(Self::new(width.unwrap_or_default(), height, cells))
+    }
+
+    pub fn cells(&self) -> &[T] {
+        &self.cells
+    }
+
+    pub fn row(&self, row: usize) -> &[T] {
+        &self.cells[row * self.width..(row + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.cells.chunks(self.width.max(1))
+    }
+
+    pub fn column(&self, col: usize) -> impl Iterator<Item = &T> {
+        self.cells.iter().skip(col).step_by(self.width.max(1))
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid::new(self.width, self.height, self.cells.iter().map(f).collect())
+    }
+
+    /// Pack the cells which satisfy `predicate` into a bit grid
+    pub fn to_bits(&self, mut predicate: impl FnMut(&T) -> bool) -> BitGrid {
+        BitGrid {
+            bits: self.cells.iter().map(&mut predicate).collect(),
+            width: self.width,
+            height: self.height,
+        }
+    }
+
+    /// Grid with rows and columns swapped
+    pub fn transposed(&self) -> Self
+    where
+        T: Clone,
+    {
+        self.remapped(self.height, self.width, |row, col| (col, row))
+    }
+
+    /// Grid rotated by a quarter turn clockwise
+    pub fn rotated_clockwise(&self) -> Self
+    where
+        T: Clone,
+    {
+        let last_row = self.height.saturating_sub(1);
+        self.remapped(self.height, self.width, |row, col| (last_row - col, row))
+    }
+
+    /// Grid rotated by a quarter turn counter-clockwise
+    pub fn rotated_counter_clockwise(&self) -> Self
+    where
+        T: Clone,
+    {
+        let last_col = self.width.saturating_sub(1);
+        self.remapped(self.height, self.width, |row, col| (col, last_col - row))
+    }
+
+    /// Grid of `width` and `height` whose cell at `(row, col)` is taken from `source(row, col)`
+    fn remapped(
+        &self,
+        width: usize,
+        height: usize,
+        source: impl Fn(usize, usize) -> (usize, usize),
+    ) -> Self
+    where
+        T: Clone,
+    {
+        let cells = (0..height)
+            .flat_map(|row| (0..width).map(move |col| (row, col)))
+            .map(|(row, col)| {
+                let (r, c) = source(row, col);
+                self.cells[self.position(r, c)].clone()
+            })
+            .collect();
+        Self::new(width, height, cells)
+    }
+}
+
+impl<T> Index<Position> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, position: Position) -> &Self::Output {
+        &self.cells[position]
+    }
+}
+
+impl<T> IndexMut<Position> for Grid<T> {
+    fn index_mut(&mut self, position: Position) -> &mut Self::Output {
+        &mut self.cells[position]
+    }
+}
+
+impl<T> Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, (row, col): (usize, usize)) -> &Self::Output {
+        &self.cells[self.position(row, col)]
+    }
+}
+
+impl<T: Display> Display for Grid<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for row in self.rows() {
+            for cell in row {
+                write!(f, "{cell}")?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+/// Row-major grid of bits packed into a `BitVec`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BitGrid {
+    bits: BitVec,
+    width: usize,
+    height: usize,
+}
+
+impl GridShape for BitGrid {
+    fn width(&self) -> usize {
+        self.width
+    }
+
+    fn height(&self) -> usize {
+        self.height
+    }
+}
+
+impl BitGrid {
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            bits: bitvec![0; width * height],
+            width,
+            height,
+        }
+    }
+
+    /// Parse the non-empty `lines` of a grid with characters in `symbols`
+    /// where `one` marks a set bit
+    pub fn parse(
+        lines: impl Iterator<Item = (usize, String)>,
+        symbols: &str,
+        one: char,
+    ) -> Result<Self, ParseError> {
+        Ok(Grid::parse(lines, symbols, |c| c == one)?.to_bits(|&bit| bit))
+    }
+
+    pub fn bits(&self) -> &BitSlice {
+        &self.bits
+    }
+
+    pub fn get(&self, position: Position) -> bool {
+        self.bits[position]
+    }
+
+    pub fn set(&mut self, position: Position, value: bool) {
+        self.bits.set(position, value)
+    }
+
+    pub fn row(&self, row: usize) -> &BitSlice {
+        &self.bits[row * self.width..(row + 1) * self.width]
+    }
+
+    pub fn count_ones(&self) -> usize {
+        self.bits.count_ones()
+    }
+
+    pub fn iter_ones(&self) -> impl Iterator<Item = Position> + '_ {
+        self.bits.iter_ones()
+    }
+
+    fn unpacked(&self) -> Grid<bool> {
+        Grid::new(
+            self.width,
+            self.height,
+            self.bits.iter().by_vals().collect(),
+        )
+    }
+
+    pub fn transposed(&self) -> Self {
+        self.unpacked().transposed().to_bits(|&bit| bit)
+    }
+
+    pub fn rotated_clockwise(&self) -> Self {
+        self.unpacked().rotated_clockwise().to_bits(|&bit| bit)
+    }
+
+    pub fn rotated_counter_clockwise(&self) -> Self {
+        self.unpacked()
+            .rotated_counter_clockwise()
+            .to_bits(|&bit| bit)
+    }
+}
+
+impl Display for BitGrid {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for row in 0..self.height {
+            for bit in self.row(row).iter().by_vals() {
+                write!(f, "{}", if bit { '#' } else { '.' })?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn lines(text: &'static str) -> impl Iterator<Item = (usize, String)> {
+        text.split('\n').map(String::from).enumerate()
+    }
+
+    #[test]
+    fn test_parse() {
+        let grid = Grid::parse(lines("abc\ndef\n"), "abcdef", |c| c).unwrap();
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid[(1, 2)], 'f');
+        assert_eq!(grid.row(1), ['d', 'e', 'f']);
+        assert_eq!(grid.column(1).collect::<String>(), "be");
+        assert_eq!(grid.to_string(), "abc\ndef\n");
+        assert_eq!(
+            Grid::parse(lines("ab\nabc"), "abc", |c| c),
+            Err(ParseError::new(1, 2, "row of width 2"))
+        );
+    }
+
+    #[test]
+    fn test_offset() {
+        let grid = Grid::filled(3, 2, 0);
+        assert_eq!(grid.offset(0, Vector::new(1, 2)), Some(5));
+        assert_eq!(grid.step(0, Direction::Up), None);
+        assert_eq!(grid.step(2, Direction::Right), None);
+        assert_eq!(grid.neighbours(4).collect::<Vec<_>>(), [5, 1, 3]);
+    }
+
+    #[test]
+    fn test_rotate() {
+        let grid = Grid::parse(lines("abc\ndef"), "abcdef", |c| c).unwrap();
+        assert_eq!(grid.transposed().to_string(), "ad\nbe\ncf\n");
+        assert_eq!(grid.rotated_clockwise().to_string(), "da\neb\nfc\n");
+        assert_eq!(grid.rotated_counter_clockwise().to_string(), "cf\nbe\nad\n");
+        assert_eq!(grid.rotated_clockwise().rotated_counter_clockwise(), grid);
+
+        let bits = BitGrid::parse(lines("#..\n##."), ".#", '#').unwrap();
+        assert_eq!(bits.count_ones(), 3);
+        assert_eq!(bits.rotated_clockwise().to_string(), "##\n#.\n..\n");
+        assert_eq!(bits.transposed().transposed(), bits);
+    }
+}
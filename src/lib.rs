@@ -1,32 +1,36 @@
+//! Solutions for the Advent of Code 2023
+//!
+//! Each day is a public module `dayN` with its parsed input model, the typed answer functions
+//! `part1` and `part2` and a [`Puzzle`] `DayN` which parses the input.
+//! [`DAYS`] registers all days to solve them by number with an [`Answer`] of any type.
+//! Puzzle constants are declared as [`Puzzle::PARAMS`], which the parts read from their [`Params`].
+
 use std::{
     fs::File,
     io::{BufReader, Lines, StdinLock},
     ops::Range,
+    time::{Duration, Instant},
 };
 
-mod day1;
-mod day10;
-mod day11;
-mod day12;
-mod day13;
-mod day14;
-mod day15;
-mod day16;
-mod day17;
-mod day18;
-mod day19;
-mod day2;
-mod day20;
-mod day21;
-mod day22;
-mod day23;
-mod day3;
-mod day4;
-mod day5;
-mod day6;
-mod day7;
-mod day8;
-mod day9;
+mod answer;
+mod cycle;
+mod error;
+mod geometry;
+mod grid;
+mod params;
+mod recurrence;
+mod search;
+
+pub use answer::Answer;
+pub use cycle::{
+    fast_forward, find_cycle_brent, find_cycle_floyd, find_cycle_hashed, Cycle, CycleHistory,
+};
+pub use error::{check_grid_row, parse_token, ParseError};
+pub use geometry::{Direction, Point, Vector};
+pub use grid::{BitGrid, Grid, GridShape, Position};
+pub use params::{Param, ParamError, ParamOverride, Params};
+pub use recurrence::{first_common_step, Recurrence};
+pub use search::{astar, bfs_layers, dijkstra, Path};
 
 pub enum PuzzleInput {
     FileLines(Lines<BufReader<File>>),
@@ -74,33 +78,236 @@ impl From<&'static str> for PuzzleInput {
     }
 }
 
-pub type PuzzleSolutionFn = fn(PuzzleInput, bool) -> String;
+/// Daily puzzle which parses its input once and solves both parts on the parsed value
+pub trait Puzzle {
+    /// Parsed puzzle input shared by both parts
+    type Input;
+
+    /// Title of the puzzle
+    const TITLE: &'static str;
+
+    /// Number of parts which have a solution
+    const PARTS: u16 = 2;
+
+    /// Tunable constants of the puzzle which the parts read from their [`Params`]
+    const PARAMS: &'static [Param] = &[];
+
+    fn parse(input: PuzzleInput) -> Result<Self::Input, ParseError>;
+
+    fn part1(input: &Self::Input, params: &Params) -> Answer;
+
+    fn part2(input: &Self::Input, params: &Params) -> Answer;
+
+    /// Input properties which the solution of a part with `params` relies on
+    /// but the puzzle text does not promise
+    fn assumptions(_input: &Self::Input, _part2: bool, _params: &Params) -> Vec<Assumption> {
+        vec![]
+    }
+}
+
+/// Checked input property which a solution relies on
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Assumption {
+    pub description: &'static str,
+    pub holds: bool,
+}
+
+impl Assumption {
+    pub fn new(description: &'static str, holds: bool) -> Self {
+        Self { description, holds }
+    }
+}
+
+/// Parse the input and solve a single part of a puzzle with `params` of that part
+pub fn solve<P: Puzzle>(
+    input: PuzzleInput,
+    part2: bool,
+    params: &Params,
+) -> Result<Answer, ParseError> {
+    let parsed = P::parse(input)?;
+    Ok(if part2 {
+        P::part2(&parsed, params)
+    } else {
+        P::part1(&parsed, params)
+    })
+}
+
+/// Answer of a single part and the time it took to solve it
+#[derive(Debug, Clone)]
+pub struct TimedAnswer {
+    pub answer: Answer,
+    pub duration: Duration,
+}
+
+/// Answers of both parts solved on the same parsed input
+#[derive(Debug, Clone)]
+pub struct TimedSolution {
+    pub parse_duration: Duration,
+    pub part1: TimedAnswer,
+    /// `None` for puzzles with a single part
+    pub part2: Option<TimedAnswer>,
+}
+
+impl TimedSolution {
+    /// Answers of all solved parts with their part number
+    pub fn parts(&self) -> impl Iterator<Item = (u16, &TimedAnswer)> {
+        [(1, Some(&self.part1)), (2, self.part2.as_ref())]
+            .into_iter()
+            .filter_map(|(part, answer)| Some((part, answer?)))
+    }
+
+    pub fn total_duration(&self) -> Duration {
+        self.parse_duration + self.parts().map(|(_, answer)| answer.duration).sum()
+    }
+}
+
+/// Parse the input once and solve all parts of a puzzle with default parameters
+/// while timing each stage
+pub fn solve_timed<P: Puzzle>(input: PuzzleInput) -> Result<TimedSolution, ParseError> {
+    let start = Instant::now();
+    let parsed = P::parse(input)?;
+    let parse_duration = start.elapsed();
+    let timed = |solve_part: fn(&P::Input, &Params) -> Answer, part| {
+        let params = Params::defaults(P::PARAMS, part);
+        let start = Instant::now();
+        let answer = solve_part(&parsed, &params);
+        TimedAnswer {
+            answer,
+            duration: start.elapsed(),
+        }
+    };
+    Ok(TimedSolution {
+        parse_duration,
+        part1: timed(P::part1, 1),
+        part2: (P::PARTS > 1).then(|| timed(P::part2, 2)),
+    })
+}
+
+/// Parse the input and check which assumptions of a part of a puzzle with `params` hold for it
+pub fn check_assumptions<P: Puzzle>(
+    input: PuzzleInput,
+    part2: bool,
+    params: &Params,
+) -> Result<Vec<Assumption>, ParseError> {
+    Ok(P::assumptions(&P::parse(input)?, part2, params))
+}
+
+pub type PuzzleSolutionFn = fn(PuzzleInput, bool, &Params) -> Result<Answer, ParseError>;
+
+pub type TimedSolutionFn = fn(PuzzleInput) -> Result<TimedSolution, ParseError>;
+
+pub type AssumptionsFn = fn(PuzzleInput, bool, &Params) -> Result<Vec<Assumption>, ParseError>;
+
+/// Metadata and solution functions of a registered day
+#[derive(Clone, Copy)]
+pub struct DayPuzzle {
+    pub day: usize,
+    pub title: &'static str,
+    pub parts: u16,
+    pub params: &'static [Param],
+    pub solve: PuzzleSolutionFn,
+    pub solve_timed: TimedSolutionFn,
+    pub check_assumptions: AssumptionsFn,
+}
+
+impl DayPuzzle {
+    const fn of<P: Puzzle>(day: usize) -> Self {
+        Self {
+            day,
+            title: P::TITLE,
+            parts: P::PARTS,
+            params: P::PARAMS,
+            solve: solve::<P>,
+            solve_timed: solve_timed::<P>,
+            check_assumptions: check_assumptions::<P>,
+        }
+    }
+
+    /// Parameter values of `part`: the declared defaults replaced by any `overrides`
+    pub fn params(&self, part: u16, overrides: &[ParamOverride]) -> Result<Params, ParamError> {
+        Params::new(self.params, part, overrides)
+    }
+}
+
+/// Declare the public day modules once and derive the registry [`DAYS`] and the
+/// exported macro `registered_days!` from them
+macro_rules! register_days {
+    ($d:tt $($day:literal => $module:ident :: $puzzle:ident,)*) => {
+        $(pub mod $module;)*
+
+        /// All registered days in ascending order
+        pub static DAYS: &[DayPuzzle] = &[$(DayPuzzle::of::<$module::$puzzle>($day)),*];
+
+        /// Invoke `$callback!(1 => day1, 2 => day2, ...)` with all registered days
+        #[macro_export]
+        macro_rules! registered_days {
+            ($d callback:ident) => {
+                $d callback!($($day => $module),*);
+            };
+        }
+    };
+}
+
+// The leading `$` is passed on as the `$` of the nested `registered_days!` macro
+register_days! {$
+    1 => day1::Day1,
+    2 => day2::Day2,
+    3 => day3::Day3,
+    4 => day4::Day4,
+    5 => day5::Day5,
+    6 => day6::Day6,
+    7 => day7::Day7,
+    8 => day8::Day8,
+    9 => day9::Day9,
+    10 => day10::Day10,
+    11 => day11::Day11,
+    12 => day12::Day12,
+    13 => day13::Day13,
+    14 => day14::Day14,
+    15 => day15::Day15,
+    16 => day16::Day16,
+    17 => day17::Day17,
+    18 => day18::Day18,
+    19 => day19::Day19,
+    20 => day20::Day20,
+    21 => day21::Day21,
+    22 => day22::Day22,
+    23 => day23::Day23,
+    24 => day24::Day24,
+    25 => day25::Day25,
+}
 
 pub fn puzzle_by_day(day: usize) -> Option<PuzzleSolutionFn> {
-    match day {
-        1 => Some(day1::solution),
-        2 => Some(day2::solution),
-        3 => Some(day3::solution),
-        4 => Some(day4::solution),
-        5 => Some(day5::solution),
-        6 => Some(day6::solution),
-        7 => Some(day7::solution),
-        8 => Some(day8::solution),
-        9 => Some(day9::solution),
-        10 => Some(day10::solution),
-        11 => Some(day11::solution),
-        12 => Some(day12::solution),
-        13 => Some(day13::solution),
-        14 => Some(day14::solution),
-        15 => Some(day15::solution),
-        16 => Some(day16::solution),
-        17 => Some(day17::solution),
-        18 => Some(day18::solution),
-        19 => Some(day19::solution),
-        20 => Some(day20::solution),
-        21 => Some(day21::solution),
-        22 => Some(day22::solution),
-        23 => Some(day23::solution),
-        _ => None,
+    day_puzzle(day).map(|puzzle| puzzle.solve)
+}
+
+pub fn day_puzzle(day: usize) -> Option<DayPuzzle> {
+    DAYS.iter().find(|puzzle| puzzle.day == day).copied()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_registry() {
+        assert!(DAYS.windows(2).all(|w| w[0].day < w[1].day));
+        assert!(DAYS
+            .iter()
+            .all(|p| (1..=25).contains(&p.day) && (1..=2).contains(&p.parts)));
+        assert!(DAYS
+            .iter()
+            .all(|p| (1..=p.parts).all(|part| p.params(part, &[]).is_ok())));
+        assert_eq!(day_puzzle(7).map(|p| p.title), Some("Camel Cards"));
+        assert!(day_puzzle(26).is_none());
+    }
+
+    #[test]
+    fn test_typed_day() {
+        let sequences =
+            day9::Day9::parse("0 3 6 9 12 15\n1 3 6 10 15 21\n10 13 16 21 30 45".into());
+        let sequences = sequences.unwrap();
+        assert_eq!(day9::part1(&sequences), 114);
+        assert_eq!(day9::part2(&sequences), 2);
     }
 }
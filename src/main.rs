@@ -1,20 +1,33 @@
-use clap::Parser;
-use puzzle::{puzzle_by_day, PuzzleInput};
+use clap::{Parser, Subcommand};
+use puzzle::{day_puzzle, Answer, ParamOverride, PuzzleInput, DAYS};
 
 use anyhow::Result;
 
 use std::fs::File;
 use std::io;
 use std::io::BufRead;
+use std::ops::RangeInclusive;
 use std::path::PathBuf;
 use std::process::exit;
 
+mod cli;
+
 #[derive(Parser, Debug)]
-#[command(author, version, about, long_about = None)]
+#[command(
+    author,
+    version,
+    about,
+    long_about = None,
+    args_conflicts_with_subcommands = true,
+    subcommand_negates_reqs = true
+)]
 struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+
     /// Day of puzzle
-    #[arg(value_name = "DAY")]
-    day: usize,
+    #[arg(value_name = "DAY", required = true)]
+    day: Option<usize>,
 
     /// Part of puzzle to run
     #[arg(value_name = "PART", default_value_t = 1, value_parser = clap::value_parser!(u16).range(1..=2))]
@@ -23,14 +36,175 @@ struct Cli {
     /// Puzzle input file, otherwise reads from stdin
     #[arg(short, long, value_name = "FILE")]
     input_file: Option<PathBuf>,
+
+    /// Check the unstated input properties the solution relies on before solving
+    #[arg(long)]
+    check_assumptions: bool,
+
+    /// Override a parameter of the day, e.g. `steps=5000` (see `list` for parameters)
+    #[arg(short, long = "param", value_name = "NAME=VALUE")]
+    params: Vec<ParamOverride>,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// List all registered days with their titles and parameters
+    List,
+    /// Run all days with inputs in `src/dayN/input.txt` and print a timing table
+    All {
+        /// Days to run, e.g. `5`, `3..=12`, `3..12` or `10..`
+        #[arg(value_name = "DAYS", default_value = "1..=25", value_parser = cli::parse_day_range)]
+        days: RangeInclusive<usize>,
+    },
+    /// Compare answers of all days with inputs against recorded answers
+    Verify {
+        /// Days to verify, e.g. `5`, `3..=12`, `3..12` or `10..`
+        #[arg(value_name = "DAYS", default_value = "1..=25", value_parser = cli::parse_day_range)]
+        days: RangeInclusive<usize>,
+
+        /// Record answers of parts which have no recorded answer yet
+        #[arg(long)]
+        record: bool,
+
+        /// File of known-correct answers
+        #[arg(short, long, value_name = "FILE", default_value = "answers.toml")]
+        answers_file: PathBuf,
+    },
+    /// Download missing puzzle inputs to `src/dayN/input.txt`
+    Fetch {
+        /// Days to fetch, e.g. `5`, `3..=12`, `3..12` or `10..`
+        #[arg(value_name = "DAYS", value_parser = cli::parse_day_range)]
+        days: RangeInclusive<usize>,
+
+        #[command(flatten)]
+        server: cli::client::ServerArgs,
+    },
+    /// Create and register a new day module from a template
+    New {
+        /// Day of puzzle
+        #[arg(value_name = "DAY")]
+        day: usize,
+
+        /// Title of the puzzle, otherwise `Day DAY`
+        #[arg(short, long)]
+        title: Option<String>,
+
+        /// File with the example input used in the generated test
+        #[arg(short, long, value_name = "FILE")]
+        example: Option<PathBuf>,
+
+        /// Expected part 1 answer of the example
+        #[arg(
+            long,
+            value_name = "ANSWER",
+            requires = "example",
+            allow_hyphen_values = true
+        )]
+        part1: Option<String>,
+
+        /// Expected part 2 answer of the example
+        #[arg(
+            long,
+            value_name = "ANSWER",
+            requires = "example",
+            allow_hyphen_values = true
+        )]
+        part2: Option<String>,
+    },
+    /// Solve a part of a day and submit the answer
+    Submit {
+        /// Day of puzzle
+        #[arg(value_name = "DAY")]
+        day: usize,
+
+        /// Part of puzzle to submit
+        #[arg(value_name = "PART", value_parser = clap::value_parser!(u16).range(1..=2))]
+        part: u16,
+
+        /// File recording all submitted answers
+        #[arg(long, value_name = "FILE", default_value = "submissions.toml")]
+        history_file: PathBuf,
+
+        #[command(flatten)]
+        server: cli::client::ServerArgs,
+    },
 }
 
 fn main() -> Result<()> {
     let args = Cli::parse();
-    let puzzle = puzzle_by_day(args.day).unwrap_or_else(|| {
-        eprintln!("Puzzle day {} not found!", args.day);
+    if let Some(command) = args.command {
+        let success = match command {
+            Command::List => {
+                for puzzle in DAYS {
+                    println!(
+                        "{:>2} {} ({} parts)",
+                        puzzle.day, puzzle.title, puzzle.parts
+                    );
+                    for param in puzzle.params {
+                        let [part1, part2] = param.defaults;
+                        let default = if part1 == part2 || puzzle.parts == 1 {
+                            part1.to_string()
+                        } else {
+                            format!("{part1} (part 2: {part2})")
+                        };
+                        println!("     {}={default}: {}", param.name, param.description);
+                    }
+                }
+                true
+            }
+            Command::All { days } => cli::all::run_all(days),
+            Command::Verify {
+                days,
+                record,
+                answers_file,
+            } => cli::verify::run_verify(days, answers_file, record)?,
+            Command::Fetch { days, server } => cli::fetch::run_fetch(days, &server)?,
+            Command::New {
+                day,
+                title,
+                example,
+                part1,
+                part2,
+            } => {
+                let example = example
+                    .map(|path| -> Result<_> {
+                        Ok(cli::new::Example {
+                            input: std::fs::read_to_string(path)?,
+                            answers: [part1, part2],
+                        })
+                    })
+                    .transpose()?;
+                let title = title.unwrap_or_else(|| format!("Day {day}"));
+                cli::new::run_new(day, &title, example)?;
+                true
+            }
+            Command::Submit {
+                day,
+                part,
+                history_file,
+                server,
+            } => cli::submit::run_submit(day, part, &server.client()?, history_file)?,
+        };
+        if !success {
+            exit(1);
+        }
+        return Ok(());
+    }
+    let day = args.day.expect("day is required without subcommand");
+    let puzzle = day_puzzle(day).unwrap_or_else(|| {
+        eprintln!("Puzzle day {} not found!", day);
         exit(1);
     });
+    if args.part > puzzle.parts {
+        eprintln!("Puzzle day {} has no part {}!", day, args.part);
+        exit(1);
+    }
+    let params = puzzle
+        .params(args.part, &args.params)
+        .unwrap_or_else(|err| {
+            eprintln!("Puzzle day {} has {}!", day, err);
+            exit(1);
+        });
     let lines = if let Some(path) = args.input_file {
         let file = File::open(path).unwrap_or_else(|err| {
             eprintln!("Failed opening puzzle input! {}", err);
@@ -40,8 +214,61 @@ fn main() -> Result<()> {
     } else {
         PuzzleInput::StdinLines(io::stdin().lines())
     };
-    let solution = puzzle(lines, args.part == 2);
+    let lines = if args.check_assumptions {
+        let lines: Vec<String> = lines.collect();
+        let assumptions = (puzzle.check_assumptions)(
+            PuzzleInput::StringLines(Box::new(lines.clone().into_iter())),
+            args.part == 2,
+            &params,
+        )
+        .unwrap_or_else(|err| {
+            eprintln!("Invalid puzzle input! {}", err);
+            exit(1);
+        });
+        if !cli::report_assumptions(day, &assumptions) {
+            eprintln!("Some assumptions do not hold, the answer may be wrong!");
+        }
+        PuzzleInput::StringLines(Box::new(lines.into_iter()))
+    } else {
+        lines
+    };
+    let solution = (puzzle.solve)(lines, args.part == 2, &params).unwrap_or_else(|err| {
+        eprintln!("Invalid puzzle input! {}", err);
+        exit(1);
+    });
+    if let Answer::Unsolved(reason) = solution {
+        eprintln!(
+            "Puzzle day {} part {} has no answer: {}",
+            day, args.part, reason
+        );
+        exit(1);
+    }
 
     println!("Part{}: {}", args.part, solution);
     Ok(())
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_new_negative_answers() {
+        let args = Cli::try_parse_from([
+            "aoc2023",
+            "new",
+            "9",
+            "-e",
+            "example.txt",
+            "--part1",
+            "-3",
+            "--part2",
+            "-12",
+        ])
+        .unwrap();
+        assert!(matches!(
+            args.command,
+            Some(Command::New { part1: Some(p1), part2: Some(p2), .. }) if p1 == "-3" && p2 == "-12"
+        ));
+    }
+}
@@ -0,0 +1,180 @@
+use std::{any::type_name, collections::HashMap, error::Error, fmt::Display, str::FromStr};
+
+/// Named constant of a puzzle with a default for each part,
+/// which can be overridden, e.g. with `--param steps=5000`
+#[derive(Debug, Clone, Copy)]
+pub struct Param {
+    pub name: &'static str,
+    pub description: &'static str,
+    /// Default values of part 1 and part 2
+    pub defaults: [&'static str; 2],
+    is_valid: fn(&str) -> bool,
+}
+
+fn parses_as<T: FromStr>(value: &str) -> bool {
+    value.parse::<T>().is_ok()
+}
+
+impl Param {
+    /// Parameter of type `T` with the same default for both parts
+    pub const fn new<T: FromStr>(
+        name: &'static str,
+        description: &'static str,
+        default: &'static str,
+    ) -> Self {
+        Self::per_part::<T>(name, description, [default, default])
+    }
+
+    /// Parameter of type `T` with different defaults for part 1 and part 2
+    pub const fn per_part<T: FromStr>(
+        name: &'static str,
+        description: &'static str,
+        defaults: [&'static str; 2],
+    ) -> Self {
+        Self {
+            name,
+            description,
+            defaults,
+            is_valid: parses_as::<T>,
+        }
+    }
+
+    /// Default value of `part`
+    pub fn default_for(&self, part: u16) -> &'static str {
+        self.defaults[usize::from(part.clamp(1, 2)) - 1]
+    }
+
+    pub fn is_valid(&self, value: &str) -> bool {
+        (self.is_valid)(value)
+    }
+}
+
+/// Parameter value `NAME=VALUE` given on the command line
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParamOverride {
+    pub name: String,
+    pub value: String,
+}
+
+impl FromStr for ParamOverride {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (name, value) = s
+            .split_once('=')
+            .ok_or_else(|| format!("expected NAME=VALUE, got '{s}'"))?;
+        Ok(Self {
+            name: name.trim().to_string(),
+            value: value.trim().to_string(),
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParamError {
+    Unknown(String),
+    Invalid { name: &'static str, value: String },
+}
+
+impl Display for ParamError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParamError::Unknown(name) => write!(f, "unknown parameter '{name}'"),
+            ParamError::Invalid { name, value } => {
+                write!(f, "invalid value '{value}' for parameter '{name}'")
+            }
+        }
+    }
+}
+
+impl Error for ParamError {}
+
+/// Values of the declared parameters of a puzzle for one of its parts
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Params(HashMap<&'static str, String>);
+
+impl Params {
+    /// Defaults of the `declared` parameters for `part`, replaced by any `overrides`
+    pub fn new(
+        declared: &'static [Param],
+        part: u16,
+        overrides: &[ParamOverride],
+    ) -> Result<Self, ParamError> {
+        let defaults = declared
+            .iter()
+            .map(|param| (param, param.default_for(part).to_string()));
+        let overrides = overrides.iter().map(|param_override| {
+            declared
+                .iter()
+                .find(|param| param.name == param_override.name)
+                .map(|param| (param, param_override.value.clone()))
+                .ok_or_else(|| ParamError::Unknown(param_override.name.clone()))
+        });
+        let mut values = HashMap::new();
+        for param_value in defaults.map(Ok).chain(overrides) {
+            let (param, value) = param_value?;
+            if !param.is_valid(&value) {
+                return Err(ParamError::Invalid {
+                    name: param.name,
+                    value,
+                });
+            }
+            values.insert(param.name, value);
+        }
+        Ok(Self(values))
+    }
+
+    /// Defaults of the `declared` parameters for `part`
+    pub fn defaults(declared: &'static [Param], part: u16) -> Self {
+        Self::new(declared, part, &[]).expect("parameter defaults should be valid")
+    }
+
+    /// Value of the declared parameter `name`
+    pub fn get<T: FromStr>(&self, name: &str) -> T {
+        self.0
+            .get(name)
+            .and_then(|value| value.parse().ok())
+            .unwrap_or_else(|| {
+                panic!(
+                    "parameter '{name}' should be declared with type {}",
+                    type_name::<T>()
+                )
+            })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const PARAMS: &[Param] = &[
+        Param::per_part::<usize>("steps", "steps to walk", ["64", "26501365"]),
+        Param::new::<bool>("verbose", "print each step", "false"),
+    ];
+
+    #[test]
+    fn test_params() {
+        let params = Params::defaults(PARAMS, 2);
+        assert_eq!(params.get::<usize>("steps"), 26501365);
+        assert!(!params.get::<bool>("verbose"));
+
+        let overrides = ["steps=5000".parse().unwrap()];
+        let params = Params::new(PARAMS, 1, &overrides).unwrap();
+        assert_eq!(params.get::<u64>("steps"), 5000);
+
+        assert!("steps".parse::<ParamOverride>().is_err());
+        let unknown = ["step=5".parse().unwrap()];
+        assert_eq!(
+            Params::new(PARAMS, 1, &unknown),
+            Err(ParamError::Unknown("step".into()))
+        );
+        let invalid = ["steps=-5".parse().unwrap()];
+        assert_eq!(
+            Params::new(PARAMS, 1, &invalid),
+            Err(ParamError::Invalid {
+                name: "steps",
+                value: "-5".into()
+            })
+        );
+    }
+}
@@ -0,0 +1,146 @@
+use itertools::Itertools;
+use num_integer::Integer;
+
+/// Steps of an event which occurs a few times before a cycle and then repeats with the cycle
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Recurrence {
+    /// Event steps before the cycle starts
+    pub prefix: Vec<u64>,
+    /// First step of the cycle
+    pub cycle_start: u64,
+    pub cycle_len: u64,
+    /// Event steps within the first pass through the cycle
+    pub cycle: Vec<u64>,
+}
+
+impl Recurrence {
+    /// Split the event `steps` which were seen until `cycle_start + cycle_len`
+    pub fn new(steps: Vec<u64>, cycle_start: u64, cycle_len: u64) -> Self {
+        let (prefix, cycle) = steps.into_iter().partition(|&s| s < cycle_start);
+        Self {
+            prefix,
+            cycle_start,
+            cycle_len,
+            cycle,
+        }
+    }
+
+    pub fn contains(&self, step: u64) -> bool {
+        if step < self.cycle_start {
+            self.prefix.contains(&step)
+        } else {
+            let cycle_step = self.cycle_start + (step - self.cycle_start) % self.cycle_len;
+            self.cycle.contains(&cycle_step)
+        }
+    }
+}
+
+/// Combine `x = a1 (mod n1)` and `x = a2 (mod n2)` into `x = a (mod lcm(n1, n2))`
+/// if the congruences are compatible
+fn crt((a1, n1): (i128, i128), (a2, n2): (i128, i128)) -> Option<(i128, i128)> {
+    let gcd = n1.extended_gcd(&n2);
+    if (a2 - a1) % gcd.gcd != 0 {
+        return None;
+    }
+    let lcm = n1 / gcd.gcd * n2;
+    let k = ((a2 - a1) / gcd.gcd * gcd.x).rem_euclid(n2 / gcd.gcd);
+    Some(((a1 + n1 * k).rem_euclid(lcm), lcm))
+}
+
+/// First step from `start` on which the events of all `recurrences` occur together.
+/// Returns `None` if there is no such step, if it does not fit in `u64` or if the
+/// combined cycle length of all recurrences does not fit in `i128`.
+pub fn first_common_step(recurrences: &[Recurrence], start: u64) -> Option<u64> {
+    let all_cycling = recurrences.iter().map(|r| r.cycle_start).max()?.max(start);
+    if let Some(step) =
+        (start..all_cycling).find(|&step| recurrences.iter().all(|r| r.contains(step)))
+    {
+        return Some(step);
+    }
+
+    // Merging stays below the combined cycle length, so only it has to fit
+    recurrences.iter().try_fold(1_i128, |len, recurrence| {
+        let cycle_len = i128::from(recurrence.cycle_len);
+        (len / len.gcd(&cycle_len)).checked_mul(cycle_len)
+    })?;
+
+    // All recurrences are cycling: Merge their cycle steps one recurrence at a time
+    // into the distinct residues modulo the cycle length of the merged recurrences
+    let (mut residues, mut modulus) = (vec![0], 1);
+    for recurrence in recurrences {
+        let cycle_len = i128::from(recurrence.cycle_len);
+        residues = residues
+            .into_iter()
+            .cartesian_product(&recurrence.cycle)
+            .filter_map(|(residue, &step)| {
+                crt(
+                    (residue, modulus),
+                    (i128::from(step) % cycle_len, cycle_len),
+                )
+            })
+            .map(|(residue, _)| residue)
+            .sorted_unstable()
+            .dedup()
+            .collect();
+        modulus = modulus.lcm(&cycle_len);
+    }
+    let start = i128::from(all_cycling);
+    residues
+        .into_iter()
+        .filter_map(|residue| start.checked_add((residue - start).rem_euclid(modulus)))
+        .min()
+        .and_then(|step| u64::try_from(step).ok())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_crt() {
+        assert_eq!(crt((2, 3), (3, 5)), Some((8, 15)));
+        assert_eq!(crt((1, 4), (3, 6)), Some((9, 12)));
+        assert_eq!(crt((0, 4), (1, 6)), None);
+    }
+
+    #[test]
+    fn test_first_common_step() {
+        let recurrence = |steps: &[u64], cycle_start, cycle_len| {
+            Recurrence::new(steps.to_vec(), cycle_start, cycle_len)
+        };
+        // Common step before the second recurrence starts cycling
+        assert_eq!(
+            first_common_step(&[recurrence(&[1], 0, 2), recurrence(&[3, 12], 10, 4)], 1),
+            Some(3)
+        );
+        // Steps 2, 5, 8, ... and 1, 3, 5, ...
+        assert_eq!(
+            first_common_step(&[recurrence(&[2], 1, 3), recurrence(&[1], 1, 2)], 1),
+            Some(5)
+        );
+        // Even and odd steps never meet
+        assert_eq!(
+            first_common_step(&[recurrence(&[0], 0, 2), recurrence(&[1], 0, 2)], 0),
+            None
+        );
+        // Many recurrences with several steps per cycle share few residues
+        let ghosts = vec![recurrence(&[0, 1, 2, 3, 4, 5], 0, 6); 30];
+        assert_eq!(first_common_step(&ghosts, 7), Some(7));
+        // Steps 0 (mod 2^40 - 1) meet steps 2^40 - 1 (mod 2^40) at once,
+        // but steps 1 (mod 2^40) only at (2^40 - 1)^2 beyond `u64`
+        let len = 1 << 40;
+        assert_eq!(
+            first_common_step(
+                &[recurrence(&[len - 1], 0, len), recurrence(&[0], 0, len - 1)],
+                0
+            ),
+            Some(len - 1)
+        );
+        assert_eq!(
+            first_common_step(&[recurrence(&[1], 0, len), recurrence(&[0], 0, len - 1)], 0),
+            None
+        );
+        assert!(recurrence(&[3, 12], 10, 4).contains(16));
+        assert!(!recurrence(&[3, 12], 10, 4).contains(7));
+    }
+}
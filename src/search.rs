@@ -0,0 +1,204 @@
+use std::{
+    cmp::Ordering,
+    collections::{BinaryHeap, HashMap, HashSet},
+    hash::Hash,
+};
+
+/// Cheapest path found by a search
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Path<N> {
+    pub cost: usize,
+    /// Nodes from the start to the goal, both included
+    pub nodes: Vec<N>,
+}
+
+/// Frontier entry ordered by least estimated total cost,
+/// preferring the deeper candidate on ties
+struct Candidate<N> {
+    estimate: usize,
+    cost: usize,
+    node: N,
+}
+
+impl<N> PartialEq for Candidate<N> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<N> Eq for Candidate<N> {}
+
+impl<N> Ord for Candidate<N> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.estimate.cmp(&other.estimate).reverse()).then(self.cost.cmp(&other.cost))
+    }
+}
+
+impl<N> PartialOrd for Candidate<N> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Cheapest path from any of `starts` to a node for which `is_goal` holds.
+/// `successors` yields the neighbours of a node with the cost of stepping there.
+/// `heuristic` must not overestimate the remaining cost to a goal
+/// and should not decrease by more than the cost of any step.
+pub fn astar<N, I>(
+    starts: impl IntoIterator<Item = N>,
+    mut successors: impl FnMut(&N) -> I,
+    mut heuristic: impl FnMut(&N) -> usize,
+    mut is_goal: impl FnMut(&N) -> bool,
+) -> Option<Path<N>>
+where
+    N: Clone + Hash + Eq,
+    I: IntoIterator<Item = (N, usize)>,
+{
+    // Least cost of each reached node and its predecessor on that path
+    let mut best: HashMap<N, (usize, Option<N>)> = HashMap::new();
+    let mut frontier = BinaryHeap::new();
+    for start in starts {
+        best.insert(start.clone(), (0, None));
+        frontier.push(Candidate {
+            estimate: heuristic(&start),
+            cost: 0,
+            node: start,
+        });
+    }
+
+    while let Some(Candidate { cost, node, .. }) = frontier.pop() {
+        if best[&node].0 < cost {
+            // A cheaper path to `node` has been found since it was added
+            continue;
+        }
+        if is_goal(&node) {
+            return Some(Path {
+                cost,
+                nodes: reconstruct_path(&best, node),
+            });
+        }
+        for (next, step_cost) in successors(&node) {
+            let next_cost = cost + step_cost;
+            if best
+                .get(&next)
+                .is_some_and(|&(best_cost, _)| best_cost <= next_cost)
+            {
+                continue;
+            }
+            best.insert(next.clone(), (next_cost, Some(node.clone())));
+            frontier.push(Candidate {
+                estimate: next_cost + heuristic(&next),
+                cost: next_cost,
+                node: next,
+            });
+        }
+    }
+    None
+}
+
+/// Cheapest path from any of `starts` to a node for which `is_goal` holds,
+/// which is [`astar`] without a heuristic
+pub fn dijkstra<N, I>(
+    starts: impl IntoIterator<Item = N>,
+    successors: impl FnMut(&N) -> I,
+    is_goal: impl FnMut(&N) -> bool,
+) -> Option<Path<N>>
+where
+    N: Clone + Hash + Eq,
+    I: IntoIterator<Item = (N, usize)>,
+{
+    astar(starts, successors, |_| 0, is_goal)
+}
+
+fn reconstruct_path<N: Clone + Hash + Eq>(
+    best: &HashMap<N, (usize, Option<N>)>,
+    goal: N,
+) -> Vec<N> {
+    let mut nodes = vec![goal];
+    while let Some(predecessor) = &best[nodes.last().unwrap()].1 {
+        nodes.push(predecessor.clone());
+    }
+    nodes.reverse();
+    nodes
+}
+
+/// Breadth-first layers of the nodes first reached after 0, 1, 2, ... steps from `starts`.
+/// Ends after the last layer with new nodes.
+pub fn bfs_layers<N, I>(
+    starts: impl IntoIterator<Item = N>,
+    mut successors: impl FnMut(&N) -> I,
+) -> impl Iterator<Item = Vec<N>>
+where
+    N: Clone + Hash + Eq,
+    I: IntoIterator<Item = N>,
+{
+    let mut visited: HashSet<N> = HashSet::new();
+    let mut layer: Vec<N> = starts
+        .into_iter()
+        .filter(|start| visited.insert(start.clone()))
+        .collect();
+    std::iter::from_fn(move || {
+        if layer.is_empty() {
+            return None;
+        }
+        let next = layer
+            .iter()
+            .flat_map(&mut successors)
+            .filter(|next| visited.insert(next.clone()))
+            .collect();
+        Some(std::mem::replace(&mut layer, next))
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Edges with costs of a small directed graph
+    fn edges(node: &char) -> Vec<(char, usize)> {
+        match node {
+            'a' => vec![('b', 1), ('c', 4)],
+            'b' => vec![('c', 2), ('d', 5)],
+            'c' => vec![('d', 1)],
+            'd' => vec![('a', 1)],
+            _ => vec![('e', 1)],
+        }
+    }
+
+    #[test]
+    fn test_dijkstra() {
+        let path = dijkstra(['a'], edges, |&node| node == 'd').unwrap();
+        assert_eq!(path.cost, 4);
+        assert_eq!(path.nodes, ['a', 'b', 'c', 'd']);
+
+        let path = dijkstra(['a', 'c'], edges, |&node| node == 'd').unwrap();
+        assert_eq!(path.nodes, ['c', 'd']);
+        assert_eq!(dijkstra(['a'], edges, |&node| node == 'e'), None);
+    }
+
+    #[test]
+    fn test_astar() {
+        let heuristic = |node: &char| match node {
+            'a' => 3,
+            'b' => 3,
+            'c' => 1,
+            _ => 0,
+        };
+        let path = astar(['a'], edges, heuristic, |&node| node == 'd').unwrap();
+        assert_eq!(path.cost, 4);
+        assert_eq!(path.nodes, ['a', 'b', 'c', 'd']);
+    }
+
+    #[test]
+    fn test_bfs_layers() {
+        let neighbours = |&(x, y): &(i32, i32)| {
+            [(x + 1, y), (x, y + 1)]
+                .into_iter()
+                .filter(|&(x, y)| x < 3 && y < 2)
+        };
+        let layers = bfs_layers([(0, 0)], neighbours)
+            .map(|layer| layer.len())
+            .collect::<Vec<_>>();
+        assert_eq!(layers, [1, 2, 2, 1]);
+    }
+}